@@ -1,5 +1,6 @@
 use proc_macro::TokenStream;
 use quote::quote;
+use quote::ToTokens;
 use syn::parse;
 
 #[proc_macro_derive(EnumLen)]
@@ -15,3 +16,337 @@ pub fn derive_enum_variant_count(input: TokenStream) -> TokenStream {
 	   
     expanded.into()
 }
+
+/// Associated tables of an enum's variants, instead of the free `ENUM_LEN`:
+///
+/// - `LEN`, `NAMES` for every variant, `variant_index()`, `variant_name()`
+/// - `UNITS_LEN`, `UNITS` and `random_by(pick)` for the unit variants, where
+///   `pick(n)` returns an index below `n`
+///
+/// Unit variants marked `#[variants(skip)]` are left out of `UNITS`, and
+/// without any unit variant there is no `random_by`.
+#[proc_macro_derive(Variants, attributes(variants))]
+pub fn derive_variants(input: TokenStream) -> TokenStream {
+    let syn_item: syn::DeriveInput = parse(input).unwrap();
+    let name = &syn_item.ident;
+    let (impl_generics, ty_generics, where_clause) = syn_item.generics.split_for_impl();
+
+    let variants = match &syn_item.data {
+        syn::Data::Enum(enum_item) => &enum_item.variants,
+        _ => panic!("Variants only works on Enums"),
+    };
+
+    let len = variants.len();
+    let idents: Vec<_> = variants.iter().map(|v| &v.ident).collect();
+    let names = idents.iter().map(|ident| ident.to_string());
+    let indices = 0..len;
+
+    let units: Vec<_> = variants
+        .iter()
+        .filter(|v| matches!(v.fields, syn::Fields::Unit) && !is_skipped(v))
+        .map(|v| &v.ident)
+        .collect();
+    let units_len = units.len();
+
+    let random_by = (units_len > 0).then(|| {
+        quote! {
+            /// The unit variant at `pick(UNITS_LEN)`
+            pub fn random_by<F: FnOnce(usize) -> usize>(pick: F) -> Self {
+                let idx = pick(#units_len);
+                match Self::UNITS.into_iter().nth(idx) {
+                    Some(unit) => unit,
+                    None => panic!("variant {} out of {}", idx, #units_len),
+                }
+            }
+        }
+    });
+
+    let expanded = quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub const LEN: usize = #len;
+            pub const NAMES: [&'static str; #len] = [#(#names),*];
+            /// unit variants `random_by` picks from
+            pub const UNITS_LEN: usize = #units_len;
+            pub const UNITS: [Self; #units_len] = [#(Self::#units),*];
+
+            pub fn variant_index(&self) -> usize {
+                match self {
+                    #(Self::#idents { .. } => #indices,)*
+                }
+            }
+
+            pub fn variant_name(&self) -> &'static str {
+                Self::NAMES[self.variant_index()]
+            }
+
+            #random_by
+        }
+    };
+
+    expanded.into()
+}
+
+fn is_skipped(variant: &syn::Variant) -> bool {
+    variant.attrs.iter().any(|attr| {
+        attr.path.is_ident("variants")
+            && attr
+                .parse_args::<syn::Ident>()
+                .is_ok_and(|arg| arg == "skip")
+    })
+}
+
+/// `impl rg::Rubbish`, filling every field with `Rubbish::rubbish` or as its
+/// `#[rg(...)]` attribute says:
+///
+/// - `mode = "noun"`, any of `noun verb pred adj adverb svo slp rand uuid uuid7 ulid`
+/// - `digits = "1..=4"`, `lowers = ..`, `uppers = ..`, `hex = ..` with a length range
+/// - `range = "18..=65"` for numbers
+/// - `len = "0..=3"` for the number of items of a `Vec`
+/// - `json` for a `rg::fmt::json::Json` document
+/// - `skip` for `Default::default()`, or on an enum variant to never pick it
+///
+/// Past the nesting limit an enum only picks variants with no field naming
+/// the enum itself, if it has any, so recursive enums end.
+#[proc_macro_derive(Rubbish, attributes(rg))]
+pub fn derive_rubbish(input: TokenStream) -> TokenStream {
+    let syn_item: syn::DeriveInput = parse(input).unwrap();
+    match rubbish_impl(&syn_item) {
+        Ok(expanded) => expanded.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn rubbish_impl(item: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &item.ident;
+    let mut generics = item.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::rg::Rubbish));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &item.data {
+        syn::Data::Struct(data) => fill_fields(quote!(Self), &data.fields)?,
+        syn::Data::Enum(data) => {
+            let mut arms = Vec::new();
+            let mut leaves = Vec::new();
+            for variant in &data.variants {
+                if rg_args(&variant.attrs)?.iter().any(|arg| arg.is("skip")) {
+                    continue;
+                }
+                let ident = &variant.ident;
+                let fill = fill_fields(quote!(Self::#ident), &variant.fields)?;
+                if !variant
+                    .fields
+                    .iter()
+                    .any(|field| mentions(field.ty.to_token_stream(), name))
+                {
+                    leaves.push(arms.len());
+                }
+                arms.push(quote!(#fill,));
+            }
+            if arms.is_empty() {
+                return Err(syn::Error::new_spanned(name, "no variant to pick"));
+            }
+
+            let indices = 0..arms.len();
+            let last = arms.len() - 1;
+            let pick = if leaves.is_empty() || leaves.len() == arms.len() {
+                quote!(rg.number(0..=#last))
+            } else {
+                let last_leaf = leaves.len() - 1;
+                quote! {
+                    if ::rg::rubbish::at_max_depth() {
+                        [#(#leaves),*][rg.number(0..=#last_leaf)]
+                    } else {
+                        rg.number(0..=#last)
+                    }
+                }
+            };
+            quote! {
+                match #pick {
+                    #(#indices => #arms)*
+                    _ => unreachable!(),
+                }
+            }
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "Rubbish only works on structs and enums",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::rg::Rubbish for #name #ty_generics #where_clause {
+            fn rubbish(rg: &mut ::rg::Rg<'_>) -> Self {
+                #body
+            }
+        }
+    })
+}
+
+/// Whether `tokens` name `ty` or `Self` anywhere, e.g. in `Box<Tree>`
+fn mentions(tokens: proc_macro2::TokenStream, ty: &syn::Ident) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        proc_macro2::TokenTree::Ident(ident) => ident == *ty || ident == "Self",
+        proc_macro2::TokenTree::Group(group) => mentions(group.stream(), ty),
+        _ => false,
+    })
+}
+
+/// `path { field: .., }` or `path(.., ..)` with every field filled
+fn fill_fields(
+    path: proc_macro2::TokenStream,
+    fields: &syn::Fields,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut values = Vec::new();
+    for field in fields {
+        values.push(fill_field(field)?);
+    }
+
+    Ok(match fields {
+        syn::Fields::Named(named) => {
+            let idents = named.named.iter().map(|f| &f.ident);
+            quote!(#path { #(#idents: #values,)* })
+        }
+        syn::Fields::Unnamed(_) => quote!(#path(#(#values,)*)),
+        syn::Fields::Unit => path,
+    })
+}
+
+fn fill_field(field: &syn::Field) -> syn::Result<proc_macro2::TokenStream> {
+    let args = rg_args(&field.attrs)?;
+    let text = |others: proc_macro2::TokenStream| quote!(::core::convert::From::from(rg.once(::rg::Mode::<&str>::#others).into_owned()));
+
+    let mut len = None;
+    let mut value = None;
+    for arg in &args {
+        let expr = || arg.expr();
+        let filled = match arg.key.to_string().as_str() {
+            "skip" => quote!(::core::default::Default::default()),
+            "json" => quote!(::core::convert::From::from(
+                ::rg::fmt::json::Json::new().generate()
+            )),
+            "range" => {
+                let range = expr()?;
+                quote!(rg.number(#range))
+            }
+            "digits" | "lowers" | "uppers" | "hex" => {
+                let range = expr()?;
+                let others = match arg.key.to_string().as_str() {
+                    "digits" => quote!(Digits),
+                    "lowers" => quote!(Lowers),
+                    "uppers" => quote!(Uppers),
+                    _ => quote!(Hex),
+                };
+                text(quote!(Others(::rg::Others::#others(#range))))
+            }
+            "mode" => text(mode(arg)?),
+            "len" => {
+                len = Some(expr()?);
+                continue;
+            }
+            _ => return Err(syn::Error::new_spanned(&arg.key, "unknown rg attribute")),
+        };
+        if value.replace(filled).is_some() {
+            return Err(syn::Error::new_spanned(&arg.key, "more than one value"));
+        }
+    }
+
+    Ok(match (value, len) {
+        (Some(value), None) => value,
+        (None, None) => quote!(::rg::Rubbish::rubbish(rg)),
+        (None, Some(len)) => quote!(::rg::rubbish::rubbish_items(rg, #len)),
+        (Some(_), Some(len)) => {
+            return Err(syn::Error::new_spanned(
+                len,
+                "`len` only goes with plain items",
+            ))
+        }
+    })
+}
+
+fn mode(arg: &RgArg) -> syn::Result<proc_macro2::TokenStream> {
+    let name = match &arg.value {
+        Some(syn::Lit::Str(s)) => s.value(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &arg.key,
+                "expected `mode = \"...\"`",
+            ))
+        }
+    };
+
+    Ok(match name.as_str() {
+        "noun" => quote!(Noun),
+        "verb" => quote!(Verb),
+        "pred" => quote!(Pred),
+        "adj" => quote!(Adj),
+        "adverb" => quote!(Adverb),
+        "svo" => quote!(SVO),
+        "slp" => quote!(SLP),
+        "rand" => quote!(Rand),
+        "uuid" => quote!(Others(::rg::Others::Uuid4)),
+        "uuid7" => quote!(Others(::rg::Others::Uuid7)),
+        "ulid" => quote!(Others(::rg::Others::Ulid)),
+        _ => {
+            let msg = format!("unknown mode `{}`", name);
+            return Err(syn::Error::new_spanned(arg.value.as_ref(), msg));
+        }
+    })
+}
+
+/// `key` or `key = "value"` inside `#[rg(...)]`
+struct RgArg {
+    key: syn::Ident,
+    value: Option<syn::Lit>,
+}
+
+impl RgArg {
+    fn is(&self, key: &str) -> bool {
+        self.key == key
+    }
+
+    /// The value parsed as an expression, e.g. a range
+    fn expr(&self) -> syn::Result<syn::Expr> {
+        match &self.value {
+            Some(syn::Lit::Str(s)) => s.parse(),
+            _ => Err(syn::Error::new_spanned(
+                &self.key,
+                format!("expected `{} = \"...\"`", self.key),
+            )),
+        }
+    }
+}
+
+fn rg_args(attrs: &[syn::Attribute]) -> syn::Result<Vec<RgArg>> {
+    let mut args = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("rg")) {
+        let list = match attr.parse_meta()? {
+            syn::Meta::List(list) => list,
+            meta => return Err(syn::Error::new_spanned(meta, "expected `rg(...)`")),
+        };
+
+        for nested in list.nested {
+            let (path, value) = match nested {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) => (path, None),
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => (nv.path, Some(nv.lit)),
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "expected `key` or `key = \"...\"`",
+                    ))
+                }
+            };
+            match path.get_ident() {
+                Some(key) => args.push(RgArg {
+                    key: key.clone(),
+                    value,
+                }),
+                None => return Err(syn::Error::new_spanned(path, "expected a name")),
+            }
+        }
+    }
+    Ok(args)
+}
@@ -0,0 +1,135 @@
+use super::trace::Trace;
+use super::Generator;
+use crate::error;
+use crate::space::Space;
+use std::collections::HashMap;
+
+/// Values bound while generating one output, see [`Generator::bind`] and [`var`].
+///
+/// A new context starts with every top-level `once`, so values never leak
+/// from one output into the next.
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    vars: HashMap<String, String>,
+    /// open nodes, the root first, `None` if not tracing
+    pub(super) trace: Option<Vec<Trace>>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(String::as_str)
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.vars.insert(name.to_string(), value.to_string());
+    }
+}
+
+/// Writes the value last bound to `name`, nothing if it is unbound
+pub fn var<T: AsRef<str>>(name: T) -> Var<T> {
+    Var { name }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bind<G, T> {
+    pub(super) g: G,
+    pub(super) name: T,
+}
+
+impl<G: Generator, T: AsRef<str>> Generator for Bind<G, T> {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        let name = self.name.as_ref();
+        let start = buf.len();
+        let buf = ctx.node(
+            || format!("bind {}", name),
+            buf,
+            |buf, ctx| self.g.once_in(buf, ctx),
+        );
+        ctx.set(name, &buf[start..]);
+        buf
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        self.g.space()
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Var<T> {
+    name: T,
+}
+
+impl<T: AsRef<str>> Generator for Var<T> {
+    fn once(&mut self, buf: String) -> String {
+        buf
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        let name = self.name.as_ref();
+        ctx.node(
+            || format!("var {}", name),
+            buf,
+            |mut buf, ctx| {
+                if let Some(value) = ctx.get(name) {
+                    buf.push_str(value);
+                }
+                buf
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::var;
+    use crate::combinator::Generator;
+    use crate::combinator::RgBindMode;
+    use crate::Mode;
+    use crate::Others;
+
+    #[test]
+    fn email() {
+        let user = RgBindMode::<&str>::new(Mode::Others(Others::Lowers(4..=8)));
+        let mut g = user
+            .bind("user")
+            .tail(" <")
+            .and(var("user"))
+            .tail("@example.com>");
+
+        for _ in 0..10 {
+            let res = g.once(String::new());
+            let (name, email) = res.split_once(" <").unwrap();
+            assert_eq!(email, format!("{}@example.com>", name));
+        }
+    }
+
+    #[test]
+    fn tags() {
+        let tag = RgBindMode::<&str>::new(Mode::Diy(&["div", "span", "p"]));
+        let g = tag
+            .bind("tag")
+            .surround("<", ">")
+            .and(RgBindMode::<&str>::new(Mode::Noun))
+            .and(var("tag").surround("</", ">"))
+            .repeat(3);
+
+        let res = g.generate();
+        for open in ["div", "span", "p"] {
+            let open_cnt = res.matches(&format!("<{}>", open)).count();
+            assert_eq!(open_cnt, res.matches(&format!("</{}>", open)).count());
+        }
+        assert_eq!(var("tag").generate(), "");
+    }
+}
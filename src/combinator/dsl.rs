@@ -0,0 +1,336 @@
+//! A small expression language for generator trees, e.g.
+//! `(noun & verb | digits{1,3}) ~ "**" * 3`.
+//!
+//! From loosest to tightest:
+//! - `a | b | c` one of the alternatives, equally likely
+//! - `a & b` one after another
+//! - `a ~ "s"` append the string, `a * n` repeat, `a?` maybe nothing
+//! - `noun`, `digits{1,3}`, `"text"`, `( ... )`
+//!
+//! Words: `noun verb pred adj adverb svo slp rand`, `lowers uppers letters
+//! digits digits_nonzero hex nanoid` with a length `{n}` or `{m,n}` (default
+//! `{1}`), `int{m,n}`, `uuid uuid7 ulid`.
+
+use super::literal;
+use super::select_owned;
+use super::Generator;
+use super::RgBindMode;
+use crate::id::NANOID_ALPHABET;
+use crate::Mode;
+use crate::Others;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+type Boxed = Box<dyn Generator + Send>;
+
+/// Deepest nesting of parentheses, deeper sources are refused rather than
+/// overflowing the stack
+const MAX_DEPTH: usize = 64;
+
+/// Where and why [`parse`] failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// byte offset into the source
+    pub pos: usize,
+    pub msg: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {}: {}", self.pos, self.msg)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Builds the generator tree described by `src`
+pub fn parse(src: &str) -> Result<Boxed, ParseError> {
+    let mut parser = Parser {
+        src,
+        pos: 0,
+        depth: 0,
+    };
+    let g = parser.expr()?;
+
+    parser.skip_ws();
+    match parser.peek() {
+        None => Ok(g),
+        Some(c) => parser.error(format!("unexpected `{}`", c)),
+    }
+}
+
+struct Parser<'s> {
+    src: &'s str,
+    pos: usize,
+    /// open parentheses around `pos`
+    depth: usize,
+}
+
+impl<'s> Parser<'s> {
+    fn error<T>(&self, msg: String) -> Result<T, ParseError> {
+        Err(ParseError { pos: self.pos, msg })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    /// Skips whitespace and `c` if it comes next
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_ws();
+        let found = self.peek() == Some(c);
+        if found {
+            self.bump();
+        }
+        found
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            self.error(format!("expected `{}`", c))
+        }
+    }
+
+    fn expr(&mut self) -> Result<Boxed, ParseError> {
+        let mut alternatives = vec![self.and()?];
+        while self.eat('|') {
+            alternatives.push(self.and()?);
+        }
+
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            select_owned(alternatives).boxed()
+        })
+    }
+
+    fn and(&mut self) -> Result<Boxed, ParseError> {
+        let mut g = self.postfix()?;
+        while self.eat('&') {
+            g = g.and(self.postfix()?).boxed();
+        }
+        Ok(g)
+    }
+
+    fn postfix(&mut self) -> Result<Boxed, ParseError> {
+        let mut g = self.atom()?;
+        loop {
+            g = if self.eat('~') {
+                self.skip_ws();
+                let tail = self.string()?;
+                g.tail(tail).boxed()
+            } else if self.eat('*') {
+                let times = self.number()?;
+                match u32::try_from(times) {
+                    Ok(times) => g.repeat(times).boxed(),
+                    Err(_) => return self.error(format!("bad repeat count {}", times)),
+                }
+            } else if self.eat('?') {
+                g.optional(0.5).boxed()
+            } else {
+                return Ok(g);
+            };
+        }
+    }
+
+    fn atom(&mut self) -> Result<Boxed, ParseError> {
+        self.skip_ws();
+        match self.peek() {
+            Some('(') => {
+                if self.depth == MAX_DEPTH {
+                    return self.error(format!("nested deeper than {}", MAX_DEPTH));
+                }
+                self.bump();
+                self.depth += 1;
+                let g = self.expr()?;
+                self.depth -= 1;
+                self.expect(')')?;
+                Ok(g)
+            }
+            Some('"') => Ok(literal(self.string()?).boxed()),
+            Some(c) if c.is_ascii_alphabetic() => self.word(),
+            Some(c) => self.error(format!("expected an expression, found `{}`", c)),
+            None => self.error("expected an expression, found the end".to_string()),
+        }
+    }
+
+    fn word(&mut self) -> Result<Boxed, ParseError> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            self.bump();
+        }
+        let word = &self.src[start..self.pos];
+
+        let mode = |mode: Mode<'static, &'static str>| Ok(RgBindMode::new(mode).boxed());
+        let others = |others| mode(Mode::Others(others));
+
+        match word {
+            "noun" => mode(Mode::Noun),
+            "verb" => mode(Mode::Verb),
+            "pred" => mode(Mode::Pred),
+            "adj" => mode(Mode::Adj),
+            "adverb" => mode(Mode::Adverb),
+            "svo" => mode(Mode::SVO),
+            "slp" => mode(Mode::SLP),
+            "rand" => mode(Mode::Rand),
+            "uuid" => others(Others::Uuid4),
+            "uuid7" => others(Others::Uuid7),
+            "ulid" => others(Others::Ulid),
+            "lowers" => others(Others::Lowers(self.len_range()?)),
+            "uppers" => others(Others::Uppers(self.len_range()?)),
+            "letters" => others(Others::LowersAndUppers(self.len_range()?)),
+            "digits" => others(Others::Digits(self.len_range()?)),
+            "digits_nonzero" => others(Others::DigitsNonZero(self.len_range()?)),
+            "hex" => others(Others::Hex(self.len_range()?)),
+            "nanoid" => others(Others::NanoId(
+                self.len_range()?,
+                NANOID_ALPHABET.to_string(),
+            )),
+            "int" => match self.range()? {
+                Some(rg) => others(Others::Int(rg)),
+                None => self.error("`int` needs a range like {1,100}".to_string()),
+            },
+            _ => Err(ParseError {
+                pos: start,
+                msg: format!("unknown generator `{}`", word),
+            }),
+        }
+    }
+
+    /// `{n}` or `{m,n}` right after a word, `None` if there is no `{`
+    fn range(&mut self) -> Result<Option<RangeInclusive<i64>>, ParseError> {
+        if self.peek() != Some('{') {
+            return Ok(None);
+        }
+        self.bump();
+
+        let start = self.number()?;
+        let end = if self.eat(',') { self.number()? } else { start };
+        self.expect('}')?;
+
+        if start > end {
+            return self.error(format!("empty range {{{},{}}}", start, end));
+        }
+        Ok(Some(start..=end))
+    }
+
+    fn len_range(&mut self) -> Result<RangeInclusive<u32>, ParseError> {
+        let rg = self.range()?.unwrap_or(1..=1);
+        match (u32::try_from(*rg.start()), u32::try_from(*rg.end())) {
+            (Ok(start), Ok(end)) => Ok(start..=end),
+            _ => self.error(format!("bad length {{{},{}}}", rg.start(), rg.end())),
+        }
+    }
+
+    fn number(&mut self) -> Result<i64, ParseError> {
+        self.skip_ws();
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.bump();
+        }
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.bump();
+        }
+
+        self.src[start..self.pos].parse().or_else(|_| {
+            self.pos = start;
+            self.error("expected a number".to_string())
+        })
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        if self.bump() != Some('"') {
+            self.pos = start;
+            return self.error("expected a string".to_string());
+        }
+
+        let mut s = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.bump() {
+                    Some('n') => s.push('\n'),
+                    Some('t') => s.push('\t'),
+                    Some(c @ ('"' | '\\')) => s.push(c),
+                    _ => return self.error("bad escape".to_string()),
+                },
+                Some(c) => s.push(c),
+                None => {
+                    return Err(ParseError {
+                        pos: start,
+                        msg: "unclosed string".to_string(),
+                    })
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn example() {
+        let mut g = parse(r#"(noun & verb | digits{1,3}) ~ "**" * 3"#).unwrap();
+
+        for _ in 0..10 {
+            let res = g.once(String::new());
+            assert_eq!(res.matches("**").count(), 3, "{}", res);
+        }
+
+        let mut g = parse(r#""id-" & int{-5,5} | "none""#).unwrap();
+        let res = g.once(String::new());
+        assert!(res == "none" || res.starts_with("id-"));
+
+        let res: Vec<_> = parse(r#"("a" | "b") & "\"x\""?"#)
+            .unwrap()
+            .enumerate()
+            .unwrap()
+            .collect();
+        assert_eq!(res, ["a\"x\"", "a", "b\"x\"", "b"]);
+    }
+
+    #[test]
+    fn errors() {
+        let cases = [
+            ("noun &", 6, "expected an expression, found the end"),
+            ("noun & nouns", 7, "unknown generator `nouns`"),
+            ("(noun", 5, "expected `)`"),
+            ("noun ~ \"x", 7, "unclosed string"),
+            ("digits{3,1}", 11, "empty range {3,1}"),
+            ("noun * x", 7, "expected a number"),
+            ("noun verb", 5, "unexpected `v`"),
+        ];
+
+        for (src, pos, msg) in cases {
+            let err = parse(src).err().unwrap();
+            println!("{:?}: {}", src, err);
+            assert_eq!((err.pos, &err.msg[..]), (pos, msg), "{}", src);
+        }
+
+        let deep = format!("{}noun{}", "(".repeat(64), ")".repeat(64));
+        assert!(parse(&deep).is_ok());
+        let deep = format!("{}noun{}", "(".repeat(100_000), ")".repeat(100_000));
+        let err = parse(&deep).err().unwrap();
+        assert_eq!((err.pos, &err.msg[..]), (64, "nested deeper than 64"));
+    }
+}
@@ -0,0 +1,262 @@
+//! Sequences of events from a state machine, e.g. user sessions
+//! `login → browse → add_to_cart → checkout`.
+
+use super::bind::Context;
+use super::Generator;
+use crate::config;
+use crate::error;
+use crate::util::Alias;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Column names of [`EventFormat::Csv`] rows
+pub const CSV_HEADER: &str = "session,step,state,value";
+
+/// A machine starting in `start`, see [`Markov`]
+pub fn markov<T: AsRef<str>>(start: T) -> Markov {
+    let mut markov = Markov {
+        states: Vec::new(),
+        index: HashMap::new(),
+        start: 0,
+        max_len: 100,
+        format: EventFormat::Text,
+        session: 0,
+    };
+    markov.start = markov.state_idx(start.as_ref());
+    markov
+}
+
+/// How one output, a session of events, is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventFormat {
+    /// `login(alice) → browse → checkout`
+    Text,
+    /// `{"session":0,"step":0,"state":"login","value":"alice"}` per line
+    JsonLines,
+    /// one row per event, see [`CSV_HEADER`]
+    Csv,
+}
+
+/// States with weighted transitions, each state writing its own generator's
+/// output. A session ends in a terminal state, in a state without transitions,
+/// or after `max_len` events.
+pub struct Markov {
+    states: Vec<State>,
+    index: HashMap<String, usize>,
+    start: usize,
+    max_len: usize,
+    format: EventFormat,
+    /// sessions written so far
+    session: u64,
+}
+
+struct State {
+    name: String,
+    g: Option<Box<dyn Generator + Send>>,
+    terminal: bool,
+    targets: Vec<usize>,
+    weights: Vec<u32>,
+    alias: Option<Alias>,
+}
+
+impl Markov {
+    fn state_idx(&mut self, name: &str) -> usize {
+        if let Some(idx) = self.index.get(name) {
+            return *idx;
+        }
+        self.states.push(State {
+            name: name.to_string(),
+            g: None,
+            terminal: false,
+            targets: Vec::new(),
+            weights: Vec::new(),
+            alias: None,
+        });
+        self.index.insert(name.to_string(), self.states.len() - 1);
+        self.states.len() - 1
+    }
+
+    /// What `state` writes as its value, nothing by default
+    pub fn output<G: Generator + Send + 'static>(mut self, state: &str, g: G) -> Self {
+        let idx = self.state_idx(state);
+        self.states[idx].g = Some(g.boxed());
+        self
+    }
+
+    /// From `from` to `to` with weight `weight` among the transitions of `from`
+    pub fn transition(mut self, from: &str, to: &str, weight: u32) -> Self {
+        let (from, to) = (self.state_idx(from), self.state_idx(to));
+        let state = &mut self.states[from];
+
+        state.targets.push(to);
+        state.weights.push(weight);
+        state.alias = state
+            .weights
+            .iter()
+            .any(|w| *w > 0)
+            .then(|| Alias::new(&state.weights));
+        self
+    }
+
+    /// Sessions end after reaching `state`
+    pub fn terminal(mut self, state: &str) -> Self {
+        let idx = self.state_idx(state);
+        self.states[idx].terminal = true;
+        self
+    }
+
+    /// At most `max_len` events per session
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    pub fn format(mut self, format: EventFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    fn push_event(&self, buf: &mut String, step: usize, state: &str, value: Option<&str>) {
+        match self.format {
+            EventFormat::Text => {
+                if step != 0 {
+                    buf.push_str(" → ");
+                }
+                buf.push_str(state);
+                if let Some(value) = value {
+                    let _ = write!(buf, "({})", value);
+                }
+            }
+            EventFormat::JsonLines => {
+                let _ = write!(
+                    buf,
+                    r#"{{"session":{},"step":{},"state":"#,
+                    self.session, step
+                );
+                config::write_string(buf, state);
+                if let Some(value) = value {
+                    buf.push_str(r#","value":"#);
+                    config::write_string(buf, value);
+                }
+                buf.push_str("}\n");
+            }
+            EventFormat::Csv => {
+                let _ = write!(buf, "{},{},", self.session, step);
+                push_csv(buf, state);
+                buf.push(',');
+                push_csv(buf, value.unwrap_or(""));
+                buf.push('\n');
+            }
+        }
+    }
+}
+
+fn push_csv(buf: &mut String, field: &str) {
+    if field.contains([',', '"', '\n', '\r']) {
+        let _ = write!(buf, "\"{}\"", field.replace('"', "\"\""));
+    } else {
+        buf.push_str(field);
+    }
+}
+
+impl Generator for Markov {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, mut buf: String, ctx: &mut Context) -> String {
+        let mut idx = self.start;
+        let mut value = String::new();
+
+        for step in 0..self.max_len {
+            let state = &mut self.states[idx];
+            let name = state.name.clone();
+
+            value.clear();
+            if let Some(g) = &mut state.g {
+                value = ctx.node(|| name.clone(), value, |value, ctx| g.once_in(value, ctx));
+            }
+            let has_value = state.g.is_some();
+            self.push_event(&mut buf, step, &name, has_value.then_some(&value[..]));
+
+            let state = &self.states[idx];
+            match &state.alias {
+                Some(alias) if !state.terminal => idx = state.targets[alias.sample()],
+                _ => break,
+            }
+        }
+
+        self.session += 1;
+        buf
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.states
+            .iter_mut()
+            .filter_map(|state| state.g.as_mut())
+            .try_for_each(|g| g.validate())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::markov;
+    use super::EventFormat;
+    use crate::combinator::bind::var;
+    use crate::combinator::Generator;
+    use crate::combinator::RgBindMode;
+    use crate::Mode;
+    use crate::Others;
+
+    fn shop() -> super::Markov {
+        let user = RgBindMode::<&str>::new(Mode::Others(Others::Lowers(3..=6)));
+        markov("login")
+            .output("login", user.bind("user"))
+            .output("browse", RgBindMode::<&str>::new(Mode::Noun))
+            .output("checkout", var("user"))
+            .transition("login", "browse", 1)
+            .transition("browse", "browse", 3)
+            .transition("browse", "add_to_cart", 2)
+            .transition("browse", "logout", 1)
+            .transition("add_to_cart", "browse", 1)
+            .transition("add_to_cart", "checkout", 2)
+            .transition("checkout", "logout", 1)
+            .terminal("logout")
+            .max_len(30)
+    }
+
+    #[test]
+    fn sessions() {
+        let mut g = shop();
+
+        for _ in 0..20 {
+            let res = g.once(String::new());
+            let states: Vec<_> = res.split(" → ").collect();
+
+            assert!(states[0].starts_with("login("));
+            assert!(states.len() <= 30);
+            if states.len() < 30 {
+                assert_eq!(*states.last().unwrap(), "logout");
+            }
+            // the buyer is the one who logged in
+            if let Some(checkout) = states.iter().find(|s| s.starts_with("checkout")) {
+                assert_eq!(checkout[8..], states[0][5..]);
+            }
+        }
+    }
+
+    #[test]
+    fn formats() {
+        let mut g = shop().format(EventFormat::JsonLines);
+        g.once(String::new());
+        let res = g.once(String::new());
+        println!("{}", res);
+        assert!(res.starts_with(r#"{"session":1,"step":0,"state":"login","value":""#));
+        assert!(res.ends_with("\"state\":\"logout\"}\n"));
+
+        let mut g = markov("a")
+            .output("a", RgBindMode::<&str>::new(Mode::Diy(&["x,\"y\""])))
+            .format(EventFormat::Csv);
+        assert_eq!(g.once(String::new()), "0,0,a,\"x,\"\"y\"\"\"\n");
+    }
+}
@@ -0,0 +1,1481 @@
+use crate::error;
+use crate::error::check_probability;
+use crate::error::check_range;
+use crate::error::Error;
+use crate::extend::Case;
+use crate::space::Estimate;
+use crate::space::Outputs;
+use crate::space::Space;
+use crate::util;
+use crate::Mode;
+use crate::Rg;
+use bind::Bind;
+use bind::Context;
+use core::fmt;
+use std::cell::RefCell;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::sync::Mutex;
+use trace::Trace;
+use typed::Strings;
+use unique::Seen;
+use unique::Unique;
+
+pub mod bind;
+pub mod dsl;
+pub mod markov;
+pub mod recursive;
+pub mod seq;
+pub mod trace;
+pub mod typed;
+pub mod unique;
+
+pub trait Generator {
+    fn once(&mut self, buf: String) -> String;
+
+    /// Like `once`, with the values bound so far in this output.
+    /// Combinators forward `ctx` to their children, leaves can ignore it.
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        let _ = ctx;
+        self.once(buf)
+    }
+
+    /// Every output this generator can produce, `None` if it can't be listed:
+    /// state, time, closures over earlier text (`filter`) or bound values (`var`).
+    /// `map` is listed as if it started the output.
+    fn space(&mut self) -> Option<Space<'_>> {
+        None
+    }
+
+    /// Every output in a fixed order, see [`Generator::space`]
+    fn enumerate(&mut self) -> Option<Outputs<'_>> {
+        self.space()?.outputs()
+    }
+
+    /// Size and entropy of one output, see [`Estimate`].
+    /// `or_by` branches are taken as even.
+    fn estimate(&mut self) -> Option<Estimate> {
+        Some(self.space()?.estimate())
+    }
+
+    /// `Err` for configurations that would panic or write nothing useful,
+    /// e.g. an empty `Mode::Diy` or `select`. Checks the whole tree.
+    fn validate(&mut self) -> error::Result<()> {
+        Ok(())
+    }
+
+    /// [`Generator::generate`] after [`Generator::validate`]
+    fn try_generate(mut self) -> error::Result<String>
+    where
+        Self: Sized,
+    {
+        self.validate()?;
+        Ok(self.generate())
+    }
+
+    /// One output with the tree of generators that wrote it, see [`Trace`]
+    fn explain(&mut self) -> (String, Trace) {
+        let mut ctx = Context::tracing();
+        let buf = self.once_in(String::new(), &mut ctx);
+        let trace = ctx.finish(buf.len()).expect("tracing context");
+        (buf, trace)
+    }
+
+    /// Init generate for first time
+    fn generate(mut self) -> String
+    where
+        Self: Sized,
+    {
+        self.once(String::new())
+    }
+
+    /// Init generate for first time
+    fn generate_by(mut self, buf: String) -> String
+    where
+        Self: Sized,
+    {
+        self.once(buf)
+    }
+
+    fn and<G: Generator>(self, g: G) -> And<Self, G>
+    where
+        Self: Sized,
+    {
+        And {
+            me: self,
+            another: g,
+        }
+    }
+
+    fn or<G: Generator>(self, g: G) -> OrBy<Self, G, fn() -> bool>
+    where
+        Self: Sized,
+    {
+        OrBy {
+            me: self,
+            another: g,
+            f: util::rand_or,
+            p: Some(0.5),
+        }
+    }
+
+    /// `self` with probability `p`, otherwise `g`. A `p` out of `0..=1` is
+    /// clamped into it and refused by `validate`
+    fn or_with_probability<G: Generator>(
+        self,
+        g: G,
+        p: f64,
+    ) -> OrBy<Self, G, impl FnMut() -> bool + Clone>
+    where
+        Self: Sized,
+    {
+        let chance = clamp_probability(p);
+        OrBy {
+            me: self,
+            another: g,
+            f: move || util::rand_bool(chance),
+            p: Some(p),
+        }
+    }
+
+    fn or_by<G: Generator, F>(self, g: G, f: F) -> OrBy<Self, G, F>
+    where
+        F: FnMut() -> bool,
+        Self: Sized,
+    {
+        OrBy {
+            me: self,
+            another: g,
+            f,
+            p: None,
+        }
+    }
+
+    fn repeat(self, times: u32) -> Repeat<Self>
+    where
+        Self: Sized,
+    {
+        Repeat { g: self, times }
+    }
+
+    fn tail<T: AsRef<str>>(self, tail: T) -> Tail<Self, T>
+    where
+        Self: Sized,
+    {
+        Tail { g: self, tail }
+    }
+
+    fn map<F>(self, f: F) -> Map<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(String) -> String,
+    {
+        Map { g: self, f }
+    }
+
+    fn check(self) -> Check<Self>
+    where
+        Self: Sized,
+    {
+        Check { g: self }
+    }
+
+    /// Generates with probability `p`, otherwise appends nothing. A `p` out of
+    /// `0..=1` is clamped into it and refused by `validate`
+    fn optional(self, p: f64) -> Optional<Self>
+    where
+        Self: Sized,
+    {
+        Optional { g: self, p }
+    }
+
+    /// A random count of outputs in `times`, `sep` between them
+    fn repeat_range<T: AsRef<str>>(self, times: RangeInclusive<u32>, sep: T) -> RepeatRange<Self, T>
+    where
+        Self: Sized,
+    {
+        RepeatRange {
+            g: self,
+            times,
+            sep,
+        }
+    }
+
+    fn prefix<T: AsRef<str>>(self, prefix: T) -> Surround<Self, T>
+    where
+        Self: Sized,
+    {
+        Surround {
+            g: self,
+            left: Some(prefix),
+            right: None,
+        }
+    }
+
+    fn surround<T: AsRef<str>>(self, left: T, right: T) -> Surround<Self, T>
+    where
+        Self: Sized,
+    {
+        Surround {
+            g: self,
+            left: Some(left),
+            right: Some(right),
+        }
+    }
+
+    /// A random count of outputs in `times`, a fresh output of `sep` between them
+    fn interleave<G: Generator>(self, sep: G, times: RangeInclusive<u32>) -> Interleave<Self, G>
+    where
+        Self: Sized,
+    {
+        Interleave {
+            g: self,
+            sep,
+            times,
+        }
+    }
+
+    /// Keeps at most `max_chars` chars of this generator's output
+    fn truncate(self, max_chars: usize) -> Truncate<Self>
+    where
+        Self: Sized,
+    {
+        Truncate { g: self, max_chars }
+    }
+
+    /// `Case::Mixed` flips each char randomly
+    fn to_case(self, case: Case) -> ToCase<Self>
+    where
+        Self: Sized,
+    {
+        ToCase { g: self, case }
+    }
+
+    /// Regenerates until `f` accepts the output, appending nothing after
+    /// `max_retries` rejected attempts
+    fn filter<F>(self, f: F, max_retries: u32) -> Filter<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&str) -> bool,
+    {
+        Filter {
+            g: self,
+            f,
+            max_retries,
+        }
+    }
+
+    /// Remembers this output as `name` for later [`bind::var`]s of the same output
+    fn bind<T: AsRef<str>>(self, name: T) -> Bind<Self, T>
+    where
+        Self: Sized,
+    {
+        Bind { g: self, name }
+    }
+
+    /// Distinct outputs, remembered exactly
+    fn unique(self, max_retries: u32) -> Unique<Self>
+    where
+        Self: Sized,
+    {
+        self.unique_by(Seen::exact(), max_retries)
+    }
+
+    /// Distinct outputs in bounded memory, about `fp_rate` of fresh outputs
+    /// are taken for repeats once `capacity` outputs are remembered
+    fn unique_bloom(self, capacity: usize, fp_rate: f64, max_retries: u32) -> Unique<Self>
+    where
+        Self: Sized,
+    {
+        self.unique_by(Seen::bloom(capacity, fp_rate), max_retries)
+    }
+
+    fn unique_by(self, seen: Seen, max_retries: u32) -> Unique<Self>
+    where
+        Self: Sized,
+    {
+        Unique {
+            g: self,
+            seen,
+            max_retries,
+            exhausted: false,
+        }
+    }
+
+    /// Type-erased, can be stored in structs and moved to other threads
+    fn boxed(self) -> Box<dyn Generator + Send>
+    where
+        Self: Sized + Send + 'static,
+    {
+        Box::new(self)
+    }
+
+    /// One generator behind a lock, every clone draws from it
+    fn shared(self) -> Shared<Self>
+    where
+        Self: Sized,
+    {
+        Shared {
+            g: Arc::new(Mutex::new(self)),
+        }
+    }
+
+    /// Each output as a `String` value of a [`typed::TypedGenerator`]
+    fn typed(self) -> Strings<Self>
+    where
+        Self: Sized,
+    {
+        Strings { g: self }
+    }
+}
+
+pub fn select_by<'a, F>(generators: &'a mut [&'a mut dyn Generator], f: F) -> SelectBy<'a, F>
+where
+    F: FnMut(usize) -> usize,
+{
+    SelectBy { generators, f }
+}
+
+pub fn select<'a>(generators: &'a mut [&'a mut dyn Generator]) -> SelectBy<'a, fn(usize) -> usize> {
+    SelectBy {
+        generators,
+        f: |len| util::rand_range(0..len),
+    }
+}
+
+/// Owning version of [`select_by`]
+pub fn select_owned_by<F>(generators: Vec<Box<dyn Generator + Send>>, f: F) -> Select<F>
+where
+    F: FnMut(usize) -> usize,
+{
+    Select {
+        generators,
+        f,
+        weights: None,
+    }
+}
+
+/// Owning version of [`select`]
+pub fn select_owned(generators: Vec<Box<dyn Generator + Send>>) -> Select<fn(usize) -> usize> {
+    Select {
+        generators,
+        f: |len| util::rand_range(0..len),
+        weights: None,
+    }
+}
+
+/// Outputs of `generators` in order with `sep` between them
+pub fn join<T: AsRef<str>>(generators: Vec<Box<dyn Generator + Send>>, sep: T) -> Join<T> {
+    Join { generators, sep }
+}
+
+/// Always `s`
+pub fn literal<T: AsRef<str>>(s: T) -> Literal<T> {
+    Literal { s }
+}
+
+/// Picks by weight, e.g. with `&mut [(&mut g1, 70), (&mut g2, 30)]`
+pub fn select_weighted<'a>(
+    generators: &'a mut [(&'a mut dyn Generator, u32)],
+) -> SelectWeighted<'a> {
+    let weights: Vec<_> = generators.iter().map(|(_, w)| *w).collect();
+
+    SelectWeighted {
+        alias: alias(&weights),
+        generators,
+    }
+}
+
+/// `None` when there is nothing to pick, see [`Generator::validate`]
+fn alias(weights: &[u32]) -> Option<util::Alias> {
+    weights
+        .iter()
+        .any(|w| *w > 0)
+        .then(|| util::Alias::new(weights))
+}
+
+/// Owning version of [`select_weighted`]
+pub fn select_weighted_owned(
+    generators: Vec<(Box<dyn Generator + Send>, u32)>,
+) -> Select<impl FnMut(usize) -> usize> {
+    let weights: Vec<_> = generators.iter().map(|(_, w)| *w).collect();
+    let alias = alias(&weights);
+    // nothing can be picked, so it writes nothing like an empty `select_owned`
+    let (generators, weights) = match alias {
+        Some(_) => (generators.into_iter().map(|(g, _)| g).collect(), weights),
+        None => (Vec::new(), Vec::new()),
+    };
+
+    Select {
+        generators,
+        f: move |_| alias.as_ref().map_or(0, util::Alias::sample),
+        weights: Some(weights),
+    }
+}
+
+impl<G: Generator + ?Sized> Generator for Box<G> {
+    fn once(&mut self, buf: String) -> String {
+        (**self).once(buf)
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        (**self).once_in(buf, ctx)
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        (**self).space()
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        (**self).validate()
+    }
+}
+
+impl<G: Generator + ?Sized> Generator for &mut G {
+    fn once(&mut self, buf: String) -> String {
+        (**self).once(buf)
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        (**self).once_in(buf, ctx)
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        (**self).space()
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        (**self).validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct And<G, G2> {
+    me: G,
+    another: G2,
+}
+
+impl<G, G2> Generator for And<G, G2>
+where
+    G: Generator,
+    G2: Generator,
+{
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        let buf = self.me.once_in(buf, ctx);
+        self.another.once_in(buf, ctx)
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        Some(Space::Product(vec![
+            self.me.space()?,
+            self.another.space()?,
+        ]))
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.me.validate()?;
+        self.another.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OrBy<G, G2, F> {
+    me: G,
+    another: G2,
+    f: F,
+    /// chance of `me` if known, only for estimates
+    p: Option<f64>,
+}
+
+impl<G, G2, F> Generator for OrBy<G, G2, F>
+where
+    G: Generator,
+    G2: Generator,
+    F: FnMut() -> bool,
+{
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        ctx.node(
+            || "or".to_string(),
+            buf,
+            |buf, ctx| {
+                if (self.f)() {
+                    ctx.branch(0);
+                    self.me.once_in(buf, ctx)
+                } else {
+                    ctx.branch(1);
+                    self.another.once_in(buf, ctx)
+                }
+            },
+        )
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        let p = self.p.map_or(0.5, clamp_probability);
+        Some(Space::Union(vec![
+            (p, self.me.space()?),
+            (1.0 - p, self.another.space()?),
+        ]))
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        if let Some(p) = self.p {
+            check_probability(p)?;
+        }
+        self.me.validate()?;
+        self.another.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Map<G, F> {
+    g: G,
+    f: F,
+}
+
+impl<G, F> Generator for Map<G, F>
+where
+    G: Generator,
+    F: FnMut(String) -> String,
+{
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        ctx.node(
+            || "map".to_string(),
+            buf,
+            |buf, ctx| {
+                let buf = self.g.once_in(buf, ctx);
+                ctx.rewrite(buf, &mut self.f)
+            },
+        )
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        Some(Space::Map(
+            Box::new(self.g.space()?),
+            RefCell::new(Box::new(&mut self.f)),
+        ))
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Repeat<G> {
+    g: G,
+    times: u32,
+}
+
+impl<G: Generator> Generator for Repeat<G> {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, mut buf: String, ctx: &mut Context) -> String {
+        for _ in 0..self.times {
+            buf = self.g.once_in(buf, ctx);
+        }
+        buf
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        let times = self.times;
+        Some(Space::Repeat(
+            Box::new(self.g.space()?),
+            times..=times,
+            None,
+        ))
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Tail<G, T> {
+    g: G,
+    tail: T,
+}
+
+impl<G: Generator, T: AsRef<str>> Generator for Tail<G, T> {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, mut buf: String, ctx: &mut Context) -> String {
+        buf = self.g.once_in(buf, ctx);
+        buf.push_str(self.tail.as_ref());
+        buf
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        Some(Space::Product(vec![
+            self.g.space()?,
+            Space::literal(self.tail.as_ref()),
+        ]))
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Check<G> {
+    g: G,
+}
+
+/// Prints every output of the inner generator to stderr, with its trace when
+/// called through [`Generator::explain`]
+impl<G> Generator for Check<G>
+where
+    G: Generator + fmt::Debug,
+{
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        let start = buf.len();
+        let mark = ctx.mark();
+        let buf = self.g.once_in(buf, ctx);
+
+        eprintln!("[check] {:?} => {:?}", self.g, &buf[start..]);
+        if let Some(children) = ctx.trace.as_ref().and_then(|stack| stack.last()) {
+            for child in &children.children[mark..] {
+                eprint!("{}", child.render(&buf));
+            }
+        }
+        buf
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        self.g.space()
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Optional<G> {
+    g: G,
+    p: f64,
+}
+
+impl<G: Generator> Generator for Optional<G> {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        if util::rand_bool(clamp_probability(self.p)) {
+            self.g.once_in(buf, ctx)
+        } else {
+            buf
+        }
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        let p = clamp_probability(self.p);
+        Some(Space::Union(vec![
+            (p, self.g.space()?),
+            (1.0 - p, Space::literal("")),
+        ]))
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        check_probability(self.p)?;
+        self.g.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RepeatRange<G, T> {
+    g: G,
+    times: RangeInclusive<u32>,
+    sep: T,
+}
+
+impl<G: Generator, T: AsRef<str>> Generator for RepeatRange<G, T> {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, mut buf: String, ctx: &mut Context) -> String {
+        for i in 0..util::rand_range(self.times.clone()) {
+            if i != 0 {
+                buf.push_str(self.sep.as_ref());
+            }
+            buf = self.g.once_in(buf, ctx);
+        }
+        buf
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        Some(Space::Repeat(
+            Box::new(self.g.space()?),
+            self.times.clone(),
+            Some(Box::new(Space::literal(self.sep.as_ref()))),
+        ))
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        check_range("repeat", &self.times)?;
+        self.g.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Surround<G, T> {
+    g: G,
+    left: Option<T>,
+    right: Option<T>,
+}
+
+impl<G: Generator, T: AsRef<str>> Generator for Surround<G, T> {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, mut buf: String, ctx: &mut Context) -> String {
+        if let Some(left) = &self.left {
+            buf.push_str(left.as_ref());
+        }
+        buf = self.g.once_in(buf, ctx);
+        if let Some(right) = &self.right {
+            buf.push_str(right.as_ref());
+        }
+        buf
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        let mut parts = vec![self.g.space()?];
+        if let Some(left) = &self.left {
+            parts.insert(0, Space::literal(left.as_ref()));
+        }
+        if let Some(right) = &self.right {
+            parts.push(Space::literal(right.as_ref()));
+        }
+        Some(Space::Product(parts))
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Interleave<G, G2> {
+    g: G,
+    sep: G2,
+    times: RangeInclusive<u32>,
+}
+
+impl<G: Generator, G2: Generator> Generator for Interleave<G, G2> {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, mut buf: String, ctx: &mut Context) -> String {
+        for i in 0..util::rand_range(self.times.clone()) {
+            if i != 0 {
+                buf = self.sep.once_in(buf, ctx);
+            }
+            buf = self.g.once_in(buf, ctx);
+        }
+        buf
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        Some(Space::Repeat(
+            Box::new(self.g.space()?),
+            self.times.clone(),
+            Some(Box::new(self.sep.space()?)),
+        ))
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        check_range("repeat", &self.times)?;
+        self.g.validate()?;
+        self.sep.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Truncate<G> {
+    g: G,
+    max_chars: usize,
+}
+
+impl<G: Generator> Generator for Truncate<G> {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        let start = buf.len();
+        let max_chars = self.max_chars;
+
+        ctx.node(
+            || "truncate".to_string(),
+            buf,
+            |buf, ctx| {
+                let buf = self.g.once_in(buf, ctx);
+                ctx.rewrite(buf, |mut buf| {
+                    if let Some((idx, _)) = buf[start..].char_indices().nth(max_chars) {
+                        buf.truncate(start + idx);
+                    }
+                    buf
+                })
+            },
+        )
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        let max_chars = self.max_chars;
+        Some(Space::Map(
+            Box::new(self.g.space()?),
+            RefCell::new(Box::new(move |s: String| {
+                s.chars().take(max_chars).collect()
+            })),
+        ))
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ToCase<G> {
+    g: G,
+    case: Case,
+}
+
+impl<G: Generator> Generator for ToCase<G> {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        let start = buf.len();
+        let case = self.case;
+
+        ctx.node(
+            || "case".to_string(),
+            buf,
+            |buf, ctx| {
+                let buf = self.g.once_in(buf, ctx);
+                ctx.rewrite(buf, |buf| to_case(buf, start, case))
+            },
+        )
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        let f: fn(&str) -> String = match self.case {
+            Case::Lower => str::to_lowercase,
+            Case::Upper => str::to_uppercase,
+            Case::Mixed => return None,
+        };
+        Some(Space::Map(
+            Box::new(self.g.space()?),
+            RefCell::new(Box::new(move |s: String| f(&s))),
+        ))
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+fn to_case(mut buf: String, start: usize, case: Case) -> String {
+    let cased = match case {
+        Case::Lower => buf[start..].to_lowercase(),
+        Case::Upper => buf[start..].to_uppercase(),
+        Case::Mixed => buf[start..]
+            .chars()
+            .map(|c| {
+                if util::rand_or() {
+                    c.to_ascii_uppercase()
+                } else {
+                    c.to_ascii_lowercase()
+                }
+            })
+            .collect(),
+    };
+    buf.replace_range(start.., &cased);
+    buf
+}
+
+#[derive(Debug, Clone)]
+pub struct Filter<G, F> {
+    g: G,
+    f: F,
+    max_retries: u32,
+}
+
+impl<G, F> Generator for Filter<G, F>
+where
+    G: Generator,
+    F: FnMut(&str) -> bool,
+{
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        let start = buf.len();
+        let mut buf = buf;
+
+        let mark = ctx.mark();
+
+        for _ in 0..=self.max_retries {
+            buf = self.g.once_in(buf, ctx);
+            if (self.f)(&buf[start..]) {
+                return buf;
+            }
+            buf.truncate(start);
+            ctx.rewind(mark);
+        }
+        buf
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+pub struct Join<T> {
+    generators: Vec<Box<dyn Generator + Send>>,
+    sep: T,
+}
+
+impl<T: AsRef<str>> Generator for Join<T> {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, mut buf: String, ctx: &mut Context) -> String {
+        for (i, g) in self.generators.iter_mut().enumerate() {
+            if i != 0 {
+                buf.push_str(self.sep.as_ref());
+            }
+            buf = g.once_in(buf, ctx);
+        }
+        buf
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        let mut parts = Vec::new();
+        for (i, g) in self.generators.iter_mut().enumerate() {
+            if i != 0 {
+                parts.push(Space::literal(self.sep.as_ref()));
+            }
+            parts.push(g.space()?);
+        }
+        Some(Space::Product(parts))
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.generators.iter_mut().try_for_each(|g| g.validate())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Literal<T> {
+    s: T,
+}
+
+impl<T: AsRef<str>> Generator for Literal<T> {
+    fn once(&mut self, mut buf: String) -> String {
+        buf.push_str(self.s.as_ref());
+        buf
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        Some(Space::literal(self.s.as_ref()))
+    }
+}
+
+/// `p` forced into `0..=1`, `NaN` as never
+fn clamp_probability(p: f64) -> f64 {
+    if p.is_nan() {
+        0.0
+    } else {
+        p.clamp(0.0, 1.0)
+    }
+}
+
+fn select_space<'a>(
+    spaces: impl Iterator<Item = Option<Space<'a>>>,
+    weights: &[u32],
+) -> Option<Space<'a>> {
+    let branches = weights
+        .iter()
+        .zip(spaces)
+        .map(|(w, s)| Some((*w as f64, s?)))
+        .collect::<Option<Vec<_>>>()?;
+
+    if branches.is_empty() {
+        Some(Space::literal(""))
+    } else {
+        Some(Space::Union(branches))
+    }
+}
+
+pub struct SelectBy<'a, F> {
+    generators: &'a mut [&'a mut dyn Generator],
+    f: F,
+}
+
+impl<'a, F> Generator for SelectBy<'a, F>
+where
+    F: FnMut(usize) -> usize,
+{
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        if self.generators.is_empty() {
+            return buf;
+        }
+        let idx = (self.f)(self.generators.len());
+
+        ctx.node(
+            || "select".to_string(),
+            buf,
+            |buf, ctx| {
+                ctx.branch(idx);
+                self.generators[idx].once_in(buf, ctx)
+            },
+        )
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        let weights = vec![1; self.generators.len()];
+        select_space(self.generators.iter_mut().map(|g| g.space()), &weights)
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        if self.generators.is_empty() {
+            return Err(Error::Empty("select"));
+        }
+        self.generators.iter_mut().try_for_each(|g| g.validate())
+    }
+}
+
+pub struct Select<F> {
+    generators: Vec<Box<dyn Generator + Send>>,
+    f: F,
+    /// only for estimates, uniform if `None`
+    weights: Option<Vec<u32>>,
+}
+
+impl<F> Generator for Select<F>
+where
+    F: FnMut(usize) -> usize,
+{
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        if self.generators.is_empty() {
+            return buf;
+        }
+        let idx = (self.f)(self.generators.len());
+
+        ctx.node(
+            || "select".to_string(),
+            buf,
+            |buf, ctx| {
+                ctx.branch(idx);
+                self.generators[idx].once_in(buf, ctx)
+            },
+        )
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        let weights = match &self.weights {
+            Some(weights) => weights.clone(),
+            None => vec![1; self.generators.len()],
+        };
+        select_space(self.generators.iter_mut().map(|g| g.space()), &weights)
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        if self.generators.is_empty() {
+            return Err(Error::Empty("select"));
+        }
+        self.generators.iter_mut().try_for_each(|g| g.validate())
+    }
+}
+
+pub struct SelectWeighted<'a> {
+    generators: &'a mut [(&'a mut dyn Generator, u32)],
+    alias: Option<util::Alias>,
+}
+
+impl Generator for SelectWeighted<'_> {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        let Some(alias) = &self.alias else {
+            return buf;
+        };
+        let idx = alias.sample();
+
+        ctx.node(
+            || "select".to_string(),
+            buf,
+            |buf, ctx| {
+                ctx.branch(idx);
+                self.generators[idx].0.once_in(buf, ctx)
+            },
+        )
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        let weights: Vec<_> = self.generators.iter().map(|(_, w)| *w).collect();
+        select_space(self.generators.iter_mut().map(|(g, _)| g.space()), &weights)
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        if self.alias.is_none() {
+            return Err(Error::Empty("select"));
+        }
+        self.generators
+            .iter_mut()
+            .try_for_each(|(g, _)| g.validate())
+    }
+}
+
+#[derive(Debug)]
+pub struct Shared<G> {
+    g: Arc<Mutex<G>>,
+}
+
+impl<G> Clone for Shared<G> {
+    fn clone(&self) -> Self {
+        Self {
+            g: Arc::clone(&self.g),
+        }
+    }
+}
+
+impl<G: Generator> Generator for Shared<G> {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        let mut g = self.g.lock().unwrap_or_else(|e| e.into_inner());
+        g.once_in(buf, ctx)
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        let mut g = self.g.lock().unwrap_or_else(|e| e.into_inner());
+        g.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RgBindMode<'a, S> {
+    rg: Rg<'a>,
+    mode: Mode<'a, S>,
+}
+
+impl<'a, S: AsRef<str>> Generator for RgBindMode<'a, S> {
+    fn once(&mut self, mut buf: String) -> String {
+        let _res = self.rg.core(&self.mode, &mut buf, true, true);
+        buf
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        if !ctx.is_tracing() {
+            return self.once(buf);
+        }
+        let label = mode_label(&self.mode);
+
+        ctx.node(
+            || label,
+            buf,
+            |buf, ctx| {
+                let start = buf.len();
+                let buf = self.once(buf);
+
+                let items = match &self.mode {
+                    Mode::Diy(items) => Some(*items),
+                    Mode::DiyOwned(items) => Some(&items[..]),
+                    _ => None,
+                };
+                if let Some(items) = items {
+                    let text = &buf[start..];
+                    let text = text
+                        .strip_prefix(self.rg.left_dec.unwrap_or(""))
+                        .unwrap_or(text);
+                    let text = text
+                        .strip_suffix(self.rg.right_dec.unwrap_or(""))
+                        .unwrap_or(text);
+                    if let Some(idx) = items.iter().position(|item| item.as_ref() == text) {
+                        ctx.branch(idx);
+                    }
+                }
+                buf
+            },
+        )
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        self.rg.space(&self.mode)
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.mode.validate()
+    }
+}
+
+fn mode_label<S: AsRef<str>>(mode: &Mode<'_, S>) -> String {
+    let list = |modes: &[Mode<'_, S>]| modes.iter().map(mode_label).collect::<Vec<_>>().join(", ");
+
+    match mode {
+        Mode::Noun
+        | Mode::Verb
+        | Mode::Pred
+        | Mode::Adj
+        | Mode::Adverb
+        | Mode::SVO
+        | Mode::SLP
+        | Mode::Rand => mode.variant_name().to_string(),
+        Mode::Diy(_) | Mode::DiyOwned(_) => "Diy".to_string(),
+        Mode::Others(others) => format!("{:?}", others),
+        Mode::ASVO(sep) => format!("ASVO({:?})", sep.as_ref()),
+        Mode::SVOA(sep) => format!("SVOA({:?})", sep.as_ref()),
+        Mode::ASLP(sep) => format!("ASLP({:?})", sep.as_ref()),
+        Mode::SLPA(sep) => format!("SLPA({:?})", sep.as_ref()),
+        Mode::Concat(modes) => format!("Concat({})", list(modes)),
+        Mode::OneOf(modes) => format!("OneOf({})", list(modes)),
+        Mode::Optional(mode, percent) => format!("Optional({}, {}%)", mode_label(mode), percent),
+        Mode::Repeat(mode, times, sep) => {
+            format!(
+                "Repeat({}, {:?}, {:?})",
+                mode_label(mode),
+                times,
+                sep.as_ref()
+            )
+        }
+    }
+}
+
+impl<'a, S: AsRef<str>> RgBindMode<'a, S> {
+    pub fn new(mode: Mode<'a, S>) -> Self {
+        Self {
+            rg: Rg::new(),
+            mode,
+        }
+    }
+
+    pub fn with_dec(
+        left_dec: Option<&'a str>,
+        right_dec: Option<&'a str>,
+        mode: Mode<'a, S>,
+    ) -> Self {
+        Self {
+            rg: Rg {
+                left_dec,
+                right_dec,
+                rand_pool: None,
+            },
+            mode,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Generator;
+    use super::RgBindMode;
+    use crate::combinator::join;
+    use crate::combinator::select;
+    use crate::combinator::select_owned;
+    use crate::combinator::select_weighted;
+    use crate::combinator::select_weighted_owned;
+    use crate::error::Error;
+    use crate::extend::Case;
+    use crate::Mode;
+    use crate::Others;
+
+    #[test]
+    fn test() {
+        let g: RgBindMode<&str> = RgBindMode::new(Mode::Noun);
+        let g = g.and(RgBindMode::<&str>::new(Mode::Verb));
+        let g = g.or(RgBindMode::<&str>::new(Mode::Others(Others::Digits(1..=3))));
+        let g = g.map(|mut s| {
+            s.push_str("~~");
+            s
+        });
+        let g = g.tail("**");
+        let mut g = g.repeat(3);
+        let mut g2 = RgBindMode::<&str>::new(Mode::Noun);
+        let arr: &mut [&mut dyn Generator] = &mut [&mut g, &mut g2];
+        let g = select(arr);
+
+        println!("{:?}", g.generate());
+    }
+
+    #[test]
+    fn enumerate() {
+        let letters = RgBindMode::<&str>::new(Mode::Diy(&["a", "b"]));
+        let digits = RgBindMode::<&str>::new(Mode::Others(Others::Int(1..=2)));
+        let mut g = letters.and(digits).surround("[", "]").optional(0.5);
+        let res: Vec<_> = g.enumerate().unwrap().collect();
+        assert_eq!(res, ["[a1]", "[a2]", "[b1]", "[b2]", ""]);
+
+        let mut g = RgBindMode::<&str>::new(Mode::Diy(&["x", "y"])).repeat_range(1..=2, "-");
+        assert_eq!(g.space().unwrap().len(), Some(2 + 4));
+
+        let mut g = RgBindMode::<&str>::new(Mode::Noun).filter(|s| s.len() > 3, 10);
+        assert!(g.enumerate().is_none());
+
+        let coin = RgBindMode::<&str>::new(Mode::Diy(&["heads", "tails"]));
+        let mut g = coin.or_with_probability(RgBindMode::<&str>::new(Mode::Noun), 0.99);
+        let est = g.estimate().unwrap();
+        assert!(est.bits < 2.0 && est.cardinality > Some(100));
+    }
+
+    #[test]
+    fn weighted() {
+        let mut a = RgBindMode::<&str>::new(Mode::Diy(&["a"]));
+        let mut b = RgBindMode::<&str>::new(Mode::Diy(&["b"]));
+        let pairs: &mut [(&mut dyn Generator, u32)] = &mut [(&mut a, 70), (&mut b, 30)];
+        let mut g = select_weighted(pairs);
+
+        crate::seed(42);
+        let first: String = (0..1000).map(|_| g.once(String::new())).collect();
+        crate::seed(42);
+        let second: String = (0..1000).map(|_| g.once(String::new())).collect();
+
+        assert_eq!(first, second);
+        let a_cnt = first.matches('a').count();
+        assert!((600..800).contains(&a_cnt), "{}", a_cnt);
+
+        let mut g = select_weighted_owned(vec![(a.clone().boxed(), 0), (b.clone().boxed(), 1)]);
+        assert_eq!(g.once(String::new()), "b");
+
+        let mut g = a.clone().or_with_probability(b.clone(), 0.0);
+        assert_eq!(g.once(String::new()), "b");
+
+        // out of range chances are clamped, and refused by validate
+        let mut g = a.clone().or_with_probability(b, 1.5);
+        assert_eq!(g.once(String::new()), "a");
+        assert!(matches!(g.validate(), Err(Error::Invalid(_))));
+        let mut g = a.optional(f64::NAN);
+        assert_eq!(g.once(String::new()), "");
+        assert!(g.try_generate().is_err());
+    }
+
+    fn diy(s: &'static [&'static str]) -> RgBindMode<'static, &'static str> {
+        RgBindMode::new(Mode::Diy(s))
+    }
+
+    #[test]
+    fn vocabulary() {
+        assert_eq!(diy(&["a"]).optional(0.0).generate(), "");
+        assert_eq!(diy(&["a"]).repeat_range(3..=3, ",").generate(), "a,a,a");
+        assert_eq!(
+            diy(&["a"]).surround("(", ")").prefix("f").generate(),
+            "f(a)"
+        );
+        assert_eq!(diy(&["a"]).interleave(diy(&["-"]), 2..=2).generate(), "a-a");
+        assert_eq!(
+            join(vec![diy(&["x"]).boxed(), diy(&["y"]).boxed()], " & ").generate(),
+            "x & y"
+        );
+        assert_eq!(
+            diy(&["你好世界"]).truncate(2).generate_by("<".into()),
+            "<你好"
+        );
+        assert_eq!(
+            diy(&["aBc"]).to_case(Case::Upper).generate_by("x".into()),
+            "xABC"
+        );
+
+        let even = diy(&["1", "2", "3", "4"]).filter(|s| s == "2" || s == "4", 100);
+        assert!(matches!(even.generate().as_str(), "2" | "4"));
+        assert_eq!(
+            diy(&["1"]).filter(|s| s == "2", 3).generate_by("x".into()),
+            "x"
+        );
+    }
+
+    struct Fixture {
+        name: Box<dyn Generator + Send>,
+    }
+
+    fn fixture() -> Fixture {
+        let name = RgBindMode::<String>::new(Mode::Noun).tail(String::from("_"));
+        let digits = RgBindMode::<String>::new(Mode::Others(Others::Digits(4..=4)));
+
+        Fixture {
+            name: select_owned(vec![name.boxed(), digits.clone().and(digits).boxed()]).boxed(),
+        }
+    }
+
+    #[test]
+    fn owned() {
+        let shared = fixture().name.shared();
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let mut g = shared.clone();
+                std::thread::spawn(move || {
+                    (0..10).map(|_| g.once(String::new())).collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        for w in workers {
+            for res in w.join().unwrap() {
+                assert!(res.ends_with('_') || res.len() == 8, "{}", res);
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn validate() {
+        let res = select_owned(Vec::new()).tail("!").try_generate();
+        assert_eq!(res, Err(Error::Empty("select")));
+
+        let mut a = RgBindMode::<&str>::new(Mode::Diy(&["a"]));
+        let mut gs: [(&mut dyn Generator, u32); 1] = [(&mut a, 0)];
+        assert_eq!(
+            select_weighted(&mut gs).validate(),
+            Err(Error::Empty("select"))
+        );
+        let zero = select_weighted_owned(vec![(fixture().name, 0)]);
+        assert_eq!(zero.try_generate(), Err(Error::Empty("select")));
+
+        let inverted = RgBindMode::<&str>::new(Mode::Others(Others::Hex(4..=2)));
+        assert!(fixture().name.and(inverted).try_generate().is_err());
+        assert!(fixture().name.try_generate().is_ok());
+    }
+}
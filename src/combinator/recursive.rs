@@ -0,0 +1,146 @@
+//! Generators that contain themselves, like nested lists or expressions.
+
+use super::bind::Context;
+use super::Generator;
+use crate::error;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::OnceLock;
+
+/// One generator per level, each locked only while it runs, so clones used
+/// from several threads wait for each other instead of skipping a level
+type Levels = Arc<[OnceLock<Mutex<Box<dyn Generator + Send>>>]>;
+
+/// `body` gets a handle to itself one level deeper, as often as it likes.
+/// Below `max_depth` levels the handle writes `leaf` instead.
+///
+/// `body` is called once per level, so a handle used twice costs nothing extra.
+pub fn recursive<L, B, G>(max_depth: u32, leaf: L, mut body: B) -> Rec
+where
+    L: Generator + Send + 'static,
+    B: FnMut(Rec) -> G,
+    G: Generator + Send + 'static,
+{
+    let levels: Levels = (0..=max_depth).map(|_| OnceLock::new()).collect();
+    let level = |depth| Rec {
+        levels: Arc::clone(&levels),
+        depth,
+    };
+
+    // the level at `depth` only reaches the one below, so no lock is taken twice
+    for depth in 1..=max_depth as usize {
+        let g = body(level(depth)).boxed();
+        let _ = levels[depth - 1].set(Mutex::new(g));
+    }
+    let _ = levels[max_depth as usize].set(Mutex::new(leaf.boxed()));
+
+    level(0)
+}
+
+/// One level of a [`recursive`] generator
+#[derive(Clone)]
+pub struct Rec {
+    levels: Levels,
+    depth: usize,
+}
+
+impl std::fmt::Debug for Rec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Rec").field("depth", &self.depth).finish()
+    }
+}
+
+impl Rec {
+    fn lock(&self) -> MutexGuard<'_, Box<dyn Generator + Send>> {
+        let level = self.levels[self.depth]
+            .get()
+            .expect("levels are set up front");
+        // a panic in one output leaves the generator usable for the next
+        level.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Generator for Rec {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, buf: String, ctx: &mut Context) -> String {
+        self.lock().once_in(buf, ctx)
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.lock().validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::recursive;
+    use crate::combinator::join;
+    use crate::combinator::Generator;
+    use crate::combinator::RgBindMode;
+    use crate::Mode;
+    use crate::Others;
+
+    fn digits() -> RgBindMode<'static, &'static str> {
+        RgBindMode::new(Mode::Others(Others::Digits(1..=2)))
+    }
+
+    #[test]
+    fn expressions() {
+        let mut g = recursive(3, digits(), |expr| {
+            let sum = expr.clone().tail(" + ").and(expr).surround("(", ")");
+            digits().or(sum)
+        });
+
+        for _ in 0..20 {
+            let res = g.once(String::new());
+            let mut depth = 0;
+            for c in res.chars() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                assert!((0..=3).contains(&depth), "{}", res);
+            }
+            assert_eq!(depth, 0);
+        }
+    }
+
+    #[test]
+    fn lists() {
+        let g = recursive(2, digits(), |list| {
+            join(vec![list.clone().boxed(), list.boxed()], ", ").surround("[", "]")
+        });
+        let res = g.generate();
+        println!("{}", res);
+
+        assert!(res.starts_with("[[") && !res.contains("[[["));
+        assert_eq!(res.matches('[').count(), 3);
+    }
+
+    #[test]
+    fn threads() {
+        let g = recursive(2, digits(), |list| {
+            join(vec![list.clone().boxed(), list.boxed()], ",").surround("[", "]")
+        });
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let mut g = g.clone();
+                std::thread::spawn(move || {
+                    (0..50).map(|_| g.once(String::new())).collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        for w in workers {
+            for res in w.join().unwrap() {
+                // every level written, none skipped by another thread
+                assert_eq!(res.matches('[').count(), 3, "{}", res);
+            }
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Generators with internal state, every output depends on the previous ones.
+
+use super::Generator;
+use crate::space::Space;
+use crate::time::DateTime;
+use crate::time::TimeFormat;
+use crate::time::Timeline;
+use crate::util;
+use crate::Rg;
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+/// `start`, `start + step`, `start + 2 * step`, ...
+pub fn counter(start: i64, step: i64) -> Counter {
+    Counter {
+        next: start,
+        step,
+        width: 0,
+    }
+}
+
+/// The items in order, starting over after the last one
+pub fn cycle<T: AsRef<str>>(items: Vec<T>) -> Cycle<T> {
+    Cycle { items, idx: 0 }
+}
+
+/// Every item once in random order before any repeats, like dealing cards
+pub fn deck<T: AsRef<str>>(items: Vec<T>) -> Deck<T> {
+    let dealt = items.len();
+    Deck { items, dealt }
+}
+
+/// Instants from `start`, each `step` later than the one before
+pub fn dates(start: DateTime, step: RangeInclusive<Duration>, fmt: TimeFormat) -> Dates {
+    Dates {
+        timeline: Rg::new().timeline(start, step),
+        fmt,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Counter {
+    next: i64,
+    step: i64,
+    width: usize,
+}
+
+impl Counter {
+    /// Zero-padded to at least `width` digits, e.g. `000042`
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+}
+
+impl Generator for Counter {
+    fn once(&mut self, mut buf: String) -> String {
+        let _ = write!(buf, "{:0width$}", self.next, width = self.width);
+        self.next += self.step;
+        buf
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cycle<T> {
+    items: Vec<T>,
+    idx: usize,
+}
+
+impl<T: AsRef<str>> Generator for Cycle<T> {
+    fn once(&mut self, mut buf: String) -> String {
+        if let Some(item) = self.items.get(self.idx) {
+            buf.push_str(item.as_ref());
+            self.idx = (self.idx + 1) % self.items.len();
+        }
+        buf
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        Some(Space::Choice(
+            self.items.iter().map(|s| s.as_ref().into()).collect(),
+        ))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Deck<T> {
+    items: Vec<T>,
+    /// items before this are still to deal, in shuffled order
+    dealt: usize,
+}
+
+impl<T: AsRef<str>> Generator for Deck<T> {
+    fn once(&mut self, mut buf: String) -> String {
+        if self.items.is_empty() {
+            return buf;
+        }
+
+        if self.dealt == self.items.len() {
+            // Fisher-Yates
+            for i in (1..self.items.len()).rev() {
+                self.items.swap(i, util::rand_range(0..=i));
+            }
+            self.dealt = 0;
+        }
+
+        buf.push_str(self.items[self.dealt].as_ref());
+        self.dealt += 1;
+        buf
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        Some(Space::Choice(
+            self.items.iter().map(|s| s.as_ref().into()).collect(),
+        ))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Dates {
+    timeline: Timeline,
+    fmt: TimeFormat,
+}
+
+impl Generator for Dates {
+    fn once(&mut self, mut buf: String) -> String {
+        if let Some(t) = self.timeline.next() {
+            t.write(&mut buf, &self.fmt);
+        }
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combinator::RgBindMode;
+    use crate::Mode;
+    use std::collections::HashSet;
+
+    #[test]
+    fn counter_and_cycle() {
+        let mut ids = counter(1, 1)
+            .width(4)
+            .prefix("u")
+            .and(cycle(vec![":a", ":b"]));
+        let res: Vec<_> = (0..3).map(|_| ids.once(String::new())).collect();
+
+        assert_eq!(res, ["u0001:a", "u0002:b", "u0003:a"]);
+    }
+
+    #[test]
+    fn deck_deals_everything() {
+        let mut g = deck(vec!["a", "b", "c", "d"]);
+
+        for _ in 0..3 {
+            let round: HashSet<_> = (0..4).map(|_| g.once(String::new())).collect();
+            assert_eq!(round.len(), 4);
+        }
+    }
+
+    #[test]
+    fn sequential_dates() {
+        let day = Duration::from_secs(86_400);
+        let mut g = dates(DateTime::ymd(2026, 2, 27), day..=day, TimeFormat::Chinese)
+            .tail(" ")
+            .and(RgBindMode::<&str>::new(Mode::Verb));
+        let res: Vec<_> = (0..3).map(|_| g.once(String::new())).collect();
+
+        assert!(res[0].starts_with("2026年2月27日 "));
+        assert!(res[2].starts_with("2026年3月1日 "));
+    }
+}
@@ -0,0 +1,182 @@
+//! Which generator wrote which part of an output, see [`Generator::explain`].
+
+use super::bind::Context;
+#[cfg(doc)]
+use super::Generator;
+use std::fmt::Write;
+use std::ops::Range;
+
+/// A node of the tree returned by [`Generator::explain`].
+///
+/// Only modes, branches (`or`, `select`) and bindings make nodes, the plain
+/// glue (`and`, `tail`, ...) between them does not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    pub label: String,
+    /// branch taken by `or`/`select`, entry picked from a `Mode::Diy`
+    pub branch: Option<usize>,
+    /// bytes of the output written by this node
+    pub span: Range<usize>,
+    pub children: Vec<Trace>,
+}
+
+impl Trace {
+    fn new(label: String, start: usize) -> Self {
+        Self {
+            label,
+            branch: None,
+            span: start..start,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn text<'s>(&self, output: &'s str) -> &'s str {
+        &output[self.span.clone()]
+    }
+
+    /// This node and all below it, depth first
+    pub fn iter(&self) -> impl Iterator<Item = &Trace> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+
+    /// One indented line per node, e.g. `or #1 4..9 "hello"`
+    pub fn render(&self, output: &str) -> String {
+        let mut buf = String::new();
+        self.render_with_buf(&mut buf, output, 0);
+        buf
+    }
+
+    fn render_with_buf(&self, buf: &mut String, output: &str, depth: usize) {
+        let _ = write!(buf, "{:width$}{}", "", self.label, width = depth * 2);
+        if let Some(branch) = self.branch {
+            let _ = write!(buf, " #{}", branch);
+        }
+        let _ = writeln!(buf, " {:?} {:?}", self.span, self.text(output));
+
+        for child in &self.children {
+            child.render_with_buf(buf, output, depth + 1);
+        }
+    }
+}
+
+impl Context {
+    /// A context recording a [`Trace`]
+    pub fn tracing() -> Self {
+        let mut ctx = Self::default();
+        ctx.trace = Some(vec![Trace::new("output".to_string(), 0)]);
+        ctx
+    }
+
+    pub fn is_tracing(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    /// Runs `f` as a node named `label`, if tracing
+    pub fn node<L, F>(&mut self, label: L, buf: String, f: F) -> String
+    where
+        L: FnOnce() -> String,
+        F: FnOnce(String, &mut Context) -> String,
+    {
+        let Some(stack) = &mut self.trace else {
+            return f(buf, self);
+        };
+        let start = buf.len();
+        stack.push(Trace::new(label(), start));
+
+        let buf = f(buf, self);
+
+        if let Some(stack) = &mut self.trace {
+            let mut node = stack.pop().expect("unbalanced trace");
+            node.span = start.min(buf.len())..buf.len();
+            stack.last_mut().expect("no trace root").children.push(node);
+        }
+        buf
+    }
+
+    /// Records the branch taken by the innermost node
+    pub fn branch(&mut self, idx: usize) {
+        if let Some(node) = self.trace.as_mut().and_then(|stack| stack.last_mut()) {
+            node.branch = Some(idx);
+        }
+    }
+
+    /// Children of the innermost node so far, see [`Context::rewind`]
+    pub fn mark(&self) -> usize {
+        self.trace
+            .as_ref()
+            .and_then(|stack| stack.last())
+            .map_or(0, |node| node.children.len())
+    }
+
+    /// Forgets the children recorded since `mark`, for retried or rewritten text
+    pub fn rewind(&mut self, mark: usize) {
+        if let Some(node) = self.trace.as_mut().and_then(|stack| stack.last_mut()) {
+            node.children.truncate(mark);
+        }
+    }
+
+    /// Applies `f` to `buf`, forgetting the children of the innermost node
+    /// unless `f` only appended to it
+    pub fn rewrite<F>(&mut self, buf: String, f: F) -> String
+    where
+        F: FnOnce(String) -> String,
+    {
+        if !self.is_tracing() {
+            return f(buf);
+        }
+        let before = buf.clone();
+        let buf = f(buf);
+
+        if !buf.starts_with(&before) {
+            self.rewind(0);
+        }
+        buf
+    }
+
+    /// The recorded tree, its root covering `len` bytes
+    pub fn finish(self, len: usize) -> Option<Trace> {
+        let mut root = self.trace?.into_iter().next()?;
+        root.span = 0..len;
+        Some(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::combinator::bind::var;
+    use crate::combinator::select_owned;
+    use crate::combinator::Generator;
+    use crate::combinator::RgBindMode;
+    use crate::Mode;
+    use crate::Others;
+
+    #[test]
+    fn explain() {
+        let greeting = RgBindMode::<&str>::new(Mode::Diy(&["hi", "hello"]));
+        let number = RgBindMode::<&str>::new(Mode::Others(Others::Digits(2..=2)));
+        let mut g = greeting
+            .bind("greeting")
+            .tail(" ")
+            .and(select_owned(vec![number.boxed(), var("greeting").boxed()]))
+            .map(|s| s + "!");
+
+        for _ in 0..10 {
+            let (s, trace) = g.explain();
+            println!("{}", trace.render(&s));
+
+            assert_eq!(trace.span, 0..s.len());
+            let select = trace.iter().find(|t| t.label == "select").unwrap();
+            assert!(select.branch.is_some());
+
+            let diy = trace.iter().find(|t| t.label == "Diy").unwrap();
+            let entry = ["hi", "hello"][diy.branch.unwrap()];
+            assert_eq!(diy.text(&s), entry);
+            assert!(s.starts_with(entry) && s.ends_with('!'));
+        }
+    }
+}
@@ -0,0 +1,373 @@
+//! Generators of any value, not only text.
+//!
+//! [`Generator`] chains come in through [`Generator::typed`] and go back out
+//! through [`TypedGenerator::text`], so `RgBindMode` trees keep working as leaves.
+
+use super::Generator;
+use crate::error;
+use crate::error::Error;
+use crate::util;
+use std::fmt::Display;
+use std::fmt::Write;
+
+pub trait TypedGenerator {
+    type Output;
+
+    fn next_value(&mut self) -> Self::Output;
+
+    /// `Err` if some part of the tree can't produce a value, e.g. a
+    /// [`select`] from no generators
+    fn validate(&mut self) -> error::Result<()> {
+        Ok(())
+    }
+
+    /// [`TypedGenerator::next_value`] after [`TypedGenerator::validate`]
+    fn try_next_value(&mut self) -> error::Result<Self::Output> {
+        self.validate()?;
+        Ok(self.next_value())
+    }
+
+    fn map<F, T>(self, f: F) -> Map<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Output) -> T,
+    {
+        Map { g: self, f }
+    }
+
+    /// Both values as a tuple
+    fn and<G: TypedGenerator>(self, g: G) -> And<Self, G>
+    where
+        Self: Sized,
+    {
+        And {
+            me: self,
+            another: g,
+        }
+    }
+
+    fn or<G>(self, g: G) -> OrBy<Self, G, fn() -> bool>
+    where
+        Self: Sized,
+        G: TypedGenerator<Output = Self::Output>,
+    {
+        OrBy {
+            me: self,
+            another: g,
+            f: util::rand_or,
+        }
+    }
+
+    fn or_by<G, F>(self, g: G, f: F) -> OrBy<Self, G, F>
+    where
+        Self: Sized,
+        G: TypedGenerator<Output = Self::Output>,
+        F: FnMut() -> bool,
+    {
+        OrBy {
+            me: self,
+            another: g,
+            f,
+        }
+    }
+
+    /// `times` values collected into a `Vec`
+    fn repeat(self, times: u32) -> Repeat<Self>
+    where
+        Self: Sized,
+    {
+        Repeat { g: self, times }
+    }
+
+    /// Back to a text [`Generator`], writing values with `Display`
+    fn text(self) -> Text<Self>
+    where
+        Self: Sized,
+        Self::Output: Display,
+    {
+        Text { g: self }
+    }
+
+    fn boxed<'a>(self) -> Box<dyn TypedGenerator<Output = Self::Output> + 'a>
+    where
+        Self: Sized + 'a,
+    {
+        Box::new(self)
+    }
+}
+
+impl<G: TypedGenerator + ?Sized> TypedGenerator for Box<G> {
+    type Output = G::Output;
+
+    fn next_value(&mut self) -> Self::Output {
+        (**self).next_value()
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        (**self).validate()
+    }
+}
+
+pub fn from_fn<T, F: FnMut() -> T>(f: F) -> FromFn<F> {
+    FromFn { f }
+}
+
+pub fn constant<T: Clone>(value: T) -> Constant<T> {
+    Constant { value }
+}
+
+pub fn select_by<T, F>(generators: Vec<Box<dyn TypedGenerator<Output = T>>>, f: F) -> Select<T, F>
+where
+    F: FnMut(usize) -> usize,
+{
+    Select { generators, f }
+}
+
+/// Panics when generating from an empty list, there is no value to fall back
+/// to, `validate` reports it beforehand
+pub fn select<T>(
+    generators: Vec<Box<dyn TypedGenerator<Output = T>>>,
+) -> Select<T, fn(usize) -> usize> {
+    Select {
+        generators,
+        f: |len| util::rand_range(0..len),
+    }
+}
+
+/// [`Generator`] output as a `String` value
+#[derive(Debug, Clone)]
+pub struct Strings<G> {
+    pub(super) g: G,
+}
+
+impl<G: Generator> TypedGenerator for Strings<G> {
+    type Output = String;
+
+    fn next_value(&mut self) -> String {
+        self.g.once(String::new())
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Text<G> {
+    g: G,
+}
+
+impl<G> Generator for Text<G>
+where
+    G: TypedGenerator,
+    G::Output: Display,
+{
+    fn once(&mut self, mut buf: String) -> String {
+        let _ = write!(buf, "{}", self.g.next_value());
+        buf
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FromFn<F> {
+    f: F,
+}
+
+impl<T, F: FnMut() -> T> TypedGenerator for FromFn<F> {
+    type Output = T;
+
+    fn next_value(&mut self) -> T {
+        (self.f)()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Constant<T> {
+    value: T,
+}
+
+impl<T: Clone> TypedGenerator for Constant<T> {
+    type Output = T;
+
+    fn next_value(&mut self) -> T {
+        self.value.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Map<G, F> {
+    g: G,
+    f: F,
+}
+
+impl<G, F, T> TypedGenerator for Map<G, F>
+where
+    G: TypedGenerator,
+    F: FnMut(G::Output) -> T,
+{
+    type Output = T;
+
+    fn next_value(&mut self) -> T {
+        (self.f)(self.g.next_value())
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct And<G, G2> {
+    me: G,
+    another: G2,
+}
+
+impl<G: TypedGenerator, G2: TypedGenerator> TypedGenerator for And<G, G2> {
+    type Output = (G::Output, G2::Output);
+
+    fn next_value(&mut self) -> Self::Output {
+        (self.me.next_value(), self.another.next_value())
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.me.validate()?;
+        self.another.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OrBy<G, G2, F> {
+    me: G,
+    another: G2,
+    f: F,
+}
+
+impl<G, G2, F> TypedGenerator for OrBy<G, G2, F>
+where
+    G: TypedGenerator,
+    G2: TypedGenerator<Output = G::Output>,
+    F: FnMut() -> bool,
+{
+    type Output = G::Output;
+
+    fn next_value(&mut self) -> Self::Output {
+        if (self.f)() {
+            self.me.next_value()
+        } else {
+            self.another.next_value()
+        }
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.me.validate()?;
+        self.another.validate()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Repeat<G> {
+    g: G,
+    times: u32,
+}
+
+impl<G: TypedGenerator> TypedGenerator for Repeat<G> {
+    type Output = Vec<G::Output>;
+
+    fn next_value(&mut self) -> Self::Output {
+        (0..self.times).map(|_| self.g.next_value()).collect()
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+pub struct Select<T, F> {
+    generators: Vec<Box<dyn TypedGenerator<Output = T>>>,
+    f: F,
+}
+
+impl<T, F> TypedGenerator for Select<T, F>
+where
+    F: FnMut(usize) -> usize,
+{
+    type Output = T;
+
+    fn next_value(&mut self) -> T {
+        assert!(!self.generators.is_empty(), "select from no generators");
+        let idx = (self.f)(self.generators.len());
+        self.generators[idx].next_value()
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        if self.generators.is_empty() {
+            return Err(Error::Empty("select"));
+        }
+        self.generators.iter_mut().try_for_each(|g| g.validate())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combinator::RgBindMode;
+    use crate::Mode;
+    use crate::Others;
+
+    #[derive(Debug)]
+    struct User {
+        name: String,
+        age: i64,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn domain_object() {
+        let name = RgBindMode::<&str>::new(Mode::Noun)
+            .and(RgBindMode::<&str>::new(Mode::Others(Others::Digits(2..=2))))
+            .typed();
+        let age = from_fn(|| util::rand_range(18..=65));
+        let tags = select(vec![
+            constant("vip".to_string()).boxed(),
+            RgBindMode::<&str>::new(Mode::Adj).typed().boxed(),
+        ])
+        .repeat(2);
+
+        let mut users = name
+            .and(age)
+            .and(tags)
+            .map(|((name, age), tags)| User { name, age, tags });
+
+        for _ in 0..10 {
+            let user = users.next_value();
+            assert!((18..=65).contains(&user.age));
+            assert_eq!(user.tags.len(), 2);
+            assert!(user.name.chars().rev().take(2).all(|c| c.is_ascii_digit()));
+        }
+    }
+
+    #[test]
+    fn text() {
+        let g = constant(7).or_by(constant(8), || false).repeat(3);
+        let res = g.map(|v| v.iter().sum::<i32>()).text().tail("!").generate();
+
+        assert_eq!(res, "24!");
+    }
+
+    #[test]
+    fn validate() {
+        let mut users = constant(1).and(select::<i32>(Vec::new()));
+        assert_eq!(users.try_next_value(), Err(Error::Empty("select")));
+
+        let mut g = select(vec![constant(1).boxed()]).map(|n| n + 1);
+        assert_eq!(g.try_next_value(), Ok(2));
+
+        let empty: &[&str] = &[];
+        let mut g = RgBindMode::new(Mode::Diy(empty)).typed().text();
+        assert_eq!(g.validate(), Err(Error::Empty("Mode::Diy")));
+    }
+}
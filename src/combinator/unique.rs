@@ -0,0 +1,188 @@
+use super::bind::Context;
+use super::Generator;
+use crate::error;
+use crate::error::Error;
+use crate::space::Space;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+/// Outputs seen so far.
+#[derive(Debug, Clone)]
+pub enum Seen {
+    Exact(HashSet<String>),
+    /// Memory-bounded, a false positive only costs a retry
+    Bloom(Bloom),
+}
+
+impl Seen {
+    pub fn exact() -> Self {
+        Seen::Exact(HashSet::new())
+    }
+
+    pub fn bloom(capacity: usize, fp_rate: f64) -> Self {
+        Seen::Bloom(Bloom::new(capacity, fp_rate))
+    }
+
+    /// `true` if `s` was not seen before
+    pub fn insert(&mut self, s: &str) -> bool {
+        match self {
+            Seen::Exact(set) => !set.contains(s) && set.insert(s.to_string()),
+            Seen::Bloom(bloom) => bloom.insert(s),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bloom {
+    bits: Vec<u64>,
+    len: u64,
+    hashes: u32,
+}
+
+impl Bloom {
+    /// Sized for `capacity` items at a false positive rate of `fp_rate`
+    pub fn new(capacity: usize, fp_rate: f64) -> Self {
+        assert!(0.0 < fp_rate && fp_rate < 1.0, "fp_rate out of 0..1");
+        let ln2 = std::f64::consts::LN_2;
+        let len = (-(capacity.max(1) as f64) * fp_rate.ln() / (ln2 * ln2)).ceil() as u64;
+        let hashes = ((len as f64 / capacity.max(1) as f64) * ln2)
+            .round()
+            .max(1.0) as u32;
+
+        Self {
+            bits: vec![0; len.div_ceil(64) as usize],
+            len,
+            hashes,
+        }
+    }
+
+    /// `true` if `s` was definitely not inserted before
+    pub fn insert(&mut self, s: &str) -> bool {
+        let (h1, h2) = (hash(s, 0), hash(s, 1) | 1);
+        let mut fresh = false;
+
+        for i in 0..self.hashes as u64 {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % self.len;
+            let (word, mask) = ((bit / 64) as usize, 1 << (bit % 64));
+
+            fresh |= self.bits[word] & mask == 0;
+            self.bits[word] |= mask;
+        }
+        fresh
+    }
+}
+
+fn hash(s: &str, seed: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    s.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Never repeats an output. After `max_retries` repeats in a row the space is
+/// taken as exhausted and nothing is appended any more.
+#[derive(Debug, Clone)]
+pub struct Unique<G> {
+    pub(super) g: G,
+    pub(super) seen: Seen,
+    pub(super) max_retries: u32,
+    pub(super) exhausted: bool,
+}
+
+impl<G: Generator> Unique<G> {
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// [`Error::Exhausted`] once exhausted
+    pub fn try_once(&mut self, buf: String) -> error::Result<String> {
+        let buf = self.once(buf);
+        if self.exhausted {
+            Err(Error::Exhausted)
+        } else {
+            Ok(buf)
+        }
+    }
+
+    /// Distinct outputs until exhausted
+    pub fn values(&mut self) -> impl Iterator<Item = String> + '_ {
+        std::iter::from_fn(move || self.try_once(String::new()).ok())
+    }
+}
+
+impl<G: Generator> Generator for Unique<G> {
+    fn once(&mut self, buf: String) -> String {
+        self.once_in(buf, &mut Context::new())
+    }
+
+    fn once_in(&mut self, mut buf: String, ctx: &mut Context) -> String {
+        let start = buf.len();
+        let mark = ctx.mark();
+
+        if !self.exhausted {
+            for _ in 0..=self.max_retries {
+                buf = self.g.once_in(buf, ctx);
+                if self.seen.insert(&buf[start..]) {
+                    return buf;
+                }
+                buf.truncate(start);
+                ctx.rewind(mark);
+            }
+            self.exhausted = true;
+        }
+        buf
+    }
+
+    fn space(&mut self) -> Option<Space<'_>> {
+        self.g.space()
+    }
+
+    fn validate(&mut self) -> error::Result<()> {
+        self.g.validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Seen;
+    use crate::combinator::seq::counter;
+    use crate::combinator::Generator;
+    use crate::combinator::RgBindMode;
+    use crate::error::Error;
+    use crate::Mode;
+    use crate::Rg;
+    use std::collections::HashSet;
+
+    #[test]
+    fn exhaustion() {
+        let mut g = RgBindMode::<&str>::new(Mode::Diy(&["a", "b", "c"])).unique(100);
+        let values: HashSet<_> = g.values().take(10).collect();
+
+        assert_eq!(values.len(), 3);
+        assert!(g.is_exhausted());
+        assert_eq!(g.once("x".into()), "x");
+        assert_eq!(g.try_once(String::new()), Err(Error::Exhausted));
+    }
+
+    #[test]
+    fn bloom() {
+        let mut g = counter(0, 1).unique_bloom(10_000, 0.01, 10);
+        assert_eq!(g.values().take(10_000).count(), 10_000);
+
+        let mut seen = Seen::bloom(100, 0.01);
+        assert!(seen.insert("a"));
+        assert!(!seen.insert("a"));
+    }
+
+    #[test]
+    fn iter() {
+        let rg = Rg::new();
+        let mode = Mode::Diy(&["x", "y"]);
+        let res: Vec<_> = rg.iter(&mode).unique(50).take(5).collect();
+
+        assert_eq!(res.len(), 2);
+        assert_ne!(res[0], res[1]);
+    }
+}
@@ -0,0 +1,1093 @@
+//! Saving and loading configurations as JSON, see [`Recipe`].
+//!
+//! The layout is versioned by a top-level `"version"`, missing keys take their
+//! defaults so a hand-written recipe only needs what it changes:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "seed": 42,
+//!   "rg": { "left_dec": "{", "right_dec": "}", "rand_pool": [{ "mode": "noun", "weight": 3 }] },
+//!   "modes": ["noun", { "diy": ["a", "b"] }, { "others": { "digits": [1, 3] } }],
+//!   "seps": [",", ";"],
+//!   "json": { "field_cnt": [10, 20], "string_case": "mixed", "max_level": 3 }
+//! }
+//! ```
+
+use crate::error;
+use crate::extend::Case;
+use crate::fmt::json::Json;
+use crate::time::DateTime;
+use crate::time::DurationFormat;
+use crate::time::TimeFormat;
+use crate::util;
+use crate::Mode;
+use crate::Others;
+use crate::OwnedMode;
+use crate::Rg;
+use std::fmt;
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub const VERSION: u32 = 1;
+
+/// Why a configuration could not be loaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub msg: String,
+}
+
+impl ConfigError {
+    pub(crate) fn new<T: Into<String>>(msg: T) -> Self {
+        Self { msg: msg.into() }
+    }
+
+    /// Prefixes the path of the failing key, e.g. `modes[2]: ...`
+    fn at(self, path: fmt::Arguments) -> Self {
+        Self::new(format!("{}: {}", path, self.msg))
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Everything needed to re-run a fixture: the seed, `Rg` decorators, the
+/// `combine` modes and separators, and a [`Json`] configuration.
+///
+/// Round-trips through [`Recipe::parse`] and `to_string`.
+#[derive(Debug, Default)]
+pub struct Recipe {
+    pub seed: Option<u64>,
+    pub left_dec: Option<String>,
+    pub right_dec: Option<String>,
+    modes: Vec<OwnedMode>,
+    /// see [`Rg::rand_pool`]
+    rand_pool: Vec<(OwnedMode, u32)>,
+    pub seps: Vec<String>,
+    pub json: Option<Json>,
+}
+
+impl Recipe {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The setup of `rg.combine(modes, seps)`
+    pub fn from_combine<S: AsRef<str>>(rg: &Rg<'_>, modes: &[Mode<'_, S>], seps: &[S]) -> Self {
+        let mut recipe = Self {
+            left_dec: rg.left_dec.map(str::to_string),
+            right_dec: rg.right_dec.map(str::to_string),
+            rand_pool: rg.rand_pool.as_ref().map_or_else(Vec::new, |pool| {
+                pool.modes
+                    .iter()
+                    .cloned()
+                    .zip(pool.weights.clone())
+                    .collect()
+            }),
+            seps: seps.iter().map(|s| s.as_ref().to_string()).collect(),
+            ..Self::default()
+        };
+        for mode in modes {
+            recipe.push_mode(mode);
+        }
+        recipe
+    }
+
+    pub fn push_mode<S: AsRef<str>>(&mut self, mode: &Mode<'_, S>) {
+        self.modes.push(mode.to_owned_mode());
+    }
+
+    pub fn modes(&self) -> &[OwnedMode] {
+        &self.modes
+    }
+
+    /// An `Rg` with the saved decorators and `Rand` pool
+    pub fn rg(&self) -> Rg<'_> {
+        let mut rg = Rg {
+            left_dec: self.left_dec.as_deref(),
+            right_dec: self.right_dec.as_deref(),
+            rand_pool: None,
+        };
+        rg.rand_pool(&self.rand_pool);
+        rg
+    }
+
+    /// Seeds the thread rng with the saved seed, if any
+    pub fn reseed(&self) {
+        if let Some(seed) = self.seed {
+            util::seed(seed);
+        }
+    }
+
+    /// `combine` of the saved modes and separators
+    pub fn combine(&self) -> String {
+        self.rg().combine(&self.modes, &self.seps)
+    }
+
+    /// `Err` for a mode, `Rand` pool entry or [`Json`] setting that
+    /// `try_combine` or `try_generate` would refuse
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |e: error::Error| ConfigError::new(e.to_string());
+        for (i, mode) in self.modes.iter().enumerate() {
+            mode.validate()
+                .map_err(|e| invalid(e).at(format_args!("modes[{}]", i)))?;
+        }
+        for (i, (mode, _)) in self.rand_pool.iter().enumerate() {
+            mode.validate()
+                .map_err(|e| invalid(e).at(format_args!("rg: rand_pool[{}]", i)))?;
+        }
+        if let Some(json) = &self.json {
+            json.validate()
+                .map_err(|e| invalid(e).at(format_args!("json")))?;
+        }
+        Ok(())
+    }
+
+    /// `to_string` after [`Recipe::validate`], `Err` for a `NaN` or infinite
+    /// number instead of writing JSON that can't be loaded
+    pub fn try_to_string(&self) -> Result<String, ConfigError> {
+        self.validate()?;
+        let value = self.to_value();
+        value.check_finite()?;
+        Ok(value.to_string())
+    }
+
+    /// Loads a recipe written by `to_string`, checked by [`Recipe::validate`]
+    pub fn parse(src: &str) -> Result<Self, ConfigError> {
+        let value = Value::parse(src)?;
+        let version: u32 = value.field("version")?;
+        if version != VERSION {
+            return Err(ConfigError::new(format!(
+                "unsupported version {}, expected {}",
+                version, VERSION
+            )));
+        }
+
+        let mut recipe = Self {
+            seed: value.opt_field("seed")?,
+            seps: value.opt_field("seps")?.unwrap_or_default(),
+            json: value.opt_field("json")?,
+            ..Self::default()
+        };
+        if let Some(rg) = value.get("rg") {
+            recipe.left_dec = rg
+                .opt_field("left_dec")
+                .map_err(|e| e.at(format_args!("rg")))?;
+            recipe.right_dec = rg
+                .opt_field("right_dec")
+                .map_err(|e| e.at(format_args!("rg")))?;
+            for (i, entry) in rg
+                .get("rand_pool")
+                .map_or(&[][..], Value::items)
+                .iter()
+                .enumerate()
+            {
+                let mode = entry
+                    .field("mode")
+                    .and_then(|mode| Ok((mode, entry.field("weight")?)));
+                let mode = mode.map_err(|e| e.at(format_args!("rg: rand_pool[{}]", i)))?;
+                recipe.rand_pool.push(mode);
+            }
+        }
+        for (i, mode) in value
+            .get("modes")
+            .map_or(&[][..], Value::items)
+            .iter()
+            .enumerate()
+        {
+            let mode =
+                OwnedMode::from_value(mode).map_err(|e| e.at(format_args!("modes[{}]", i)))?;
+            recipe.modes.push(mode);
+        }
+        recipe.validate()?;
+        Ok(recipe)
+    }
+}
+
+impl FromStr for Recipe {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, ConfigError> {
+        Self::parse(s)
+    }
+}
+
+/// Writes `NaN` and infinities as they are, which no reader takes back, see
+/// [`Recipe::try_to_string`]
+impl fmt::Display for Recipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_value().to_string())
+    }
+}
+
+impl Recipe {
+    fn to_value(&self) -> Value {
+        let mut obj = vec![("version".to_string(), VERSION.to_value())];
+        if let Some(seed) = self.seed {
+            obj.push(("seed".to_string(), seed.to_value()));
+        }
+
+        let mut rg = Vec::new();
+        if let Some(l) = &self.left_dec {
+            rg.push(("left_dec".to_string(), l.to_value()));
+        }
+        if let Some(r) = &self.right_dec {
+            rg.push(("right_dec".to_string(), r.to_value()));
+        }
+        if !self.rand_pool.is_empty() {
+            let pool = self.rand_pool.iter().map(|(mode, weight)| {
+                Value::Object(vec![
+                    ("mode".to_string(), mode.to_value()),
+                    ("weight".to_string(), weight.to_value()),
+                ])
+            });
+            rg.push(("rand_pool".to_string(), Value::Array(pool.collect())));
+        }
+        if !rg.is_empty() {
+            obj.push(("rg".to_string(), Value::Object(rg)));
+        }
+
+        let modes = self.modes.iter().map(Config::to_value).collect();
+        obj.push(("modes".to_string(), Value::Array(modes)));
+        obj.push(("seps".to_string(), self.seps.to_value()));
+        if let Some(json) = &self.json {
+            obj.push(("json".to_string(), json.to_value()));
+        }
+        Value::Object(obj)
+    }
+}
+
+/// A parsed JSON document
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    /// kept as written, parsed by the reader into the type it wants
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+/// Conversion to and from [`Value`]
+pub(crate) trait Config: Sized {
+    fn to_value(&self) -> Value;
+
+    fn from_value(value: &Value) -> Result<Self, ConfigError>;
+}
+
+impl Value {
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(obj) => obj.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// The items of an array, nothing for anything else
+    pub(crate) fn items(&self) -> &[Value] {
+        match self {
+            Value::Array(items) => items,
+            _ => &[],
+        }
+    }
+
+    pub(crate) fn field<T: Config>(&self, key: &str) -> Result<T, ConfigError> {
+        match self.get(key) {
+            Some(value) => T::from_value(value).map_err(|e| e.at(format_args!("{}", key))),
+            None => Err(ConfigError::new(format!("missing `{}`", key))),
+        }
+    }
+
+    /// `None` if missing or null
+    pub(crate) fn opt_field<T: Config>(&self, key: &str) -> Result<Option<T>, ConfigError> {
+        match self.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(_) => self.field(key).map(Some),
+        }
+    }
+
+    /// The only key of a `{"key": ...}` object, or a bare string as a key
+    /// without a value
+    fn tagged(&self) -> Result<(&str, Option<&Value>), ConfigError> {
+        match self {
+            Value::String(tag) => Ok((tag, None)),
+            Value::Object(obj) if obj.len() == 1 => Ok((&obj[0].0, Some(&obj[0].1))),
+            _ => Err(ConfigError::new("expected a name or a one-key object")),
+        }
+    }
+
+    fn tag(tag: &str, value: Value) -> Value {
+        Value::Object(vec![(tag.to_string(), value)])
+    }
+
+    pub(crate) fn parse(src: &str) -> Result<Value, ConfigError> {
+        let mut parser = Parser {
+            src,
+            pos: 0,
+            depth: 0,
+        };
+        let value = parser.value()?;
+
+        parser.skip_ws();
+        if parser.pos < src.len() {
+            return parser.error("trailing characters");
+        }
+        Ok(value)
+    }
+
+    /// `Err` for a number JSON has no form for, e.g. a `NaN`
+    fn check_finite(&self) -> Result<(), ConfigError> {
+        match self {
+            Value::Number(n) if !n.parse::<f64>().is_ok_and(f64::is_finite) => {
+                Err(ConfigError::new(format!("{} is not a finite number", n)))
+            }
+            Value::Array(items) => items.iter().enumerate().try_for_each(|(i, item)| {
+                item.check_finite()
+                    .map_err(|e| e.at(format_args!("[{}]", i)))
+            }),
+            Value::Object(obj) => obj.iter().try_for_each(|(key, value)| {
+                value
+                    .check_finite()
+                    .map_err(|e| e.at(format_args!("{}", key)))
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn is_scalar(&self) -> bool {
+        !matches!(self, Value::Array(_) | Value::Object(_))
+    }
+
+    fn write(&self, buf: &mut String, depth: usize) {
+        let indent = |buf: &mut String, depth: usize| {
+            buf.push('\n');
+            for _ in 0..depth {
+                buf.push_str("  ");
+            }
+        };
+
+        match self {
+            Value::Null => buf.push_str("null"),
+            Value::Bool(b) => buf.push_str(if *b { "true" } else { "false" }),
+            Value::Number(n) => buf.push_str(n),
+            Value::String(s) => write_string(buf, s),
+            Value::Array(items) if items.iter().all(Value::is_scalar) => {
+                buf.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i != 0 {
+                        buf.push_str(", ");
+                    }
+                    item.write(buf, depth);
+                }
+                buf.push(']');
+            }
+            Value::Array(items) => {
+                buf.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i != 0 {
+                        buf.push(',');
+                    }
+                    indent(buf, depth + 1);
+                    item.write(buf, depth + 1);
+                }
+                indent(buf, depth);
+                buf.push(']');
+            }
+            Value::Object(obj) if obj.is_empty() => buf.push_str("{}"),
+            Value::Object(obj) => {
+                buf.push('{');
+                for (i, (key, value)) in obj.iter().enumerate() {
+                    if i != 0 {
+                        buf.push(',');
+                    }
+                    indent(buf, depth + 1);
+                    write_string(buf, key);
+                    buf.push_str(": ");
+                    value.write(buf, depth + 1);
+                }
+                indent(buf, depth);
+                buf.push('}');
+            }
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = String::new();
+        self.write(&mut buf, 0);
+        f.write_str(&buf)
+    }
+}
+
+/// `s` as a quoted JSON string
+pub(crate) fn write_string(buf: &mut String, s: &str) {
+    buf.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(buf, "\\u{:04x}", c as u32);
+            }
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+}
+
+/// Deepest nesting of arrays and objects [`Value::parse`] takes
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'s> {
+    src: &'s str,
+    pos: usize,
+    /// arrays and objects open around `pos`
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error<T>(&self, msg: &str) -> Result<T, ConfigError> {
+        Err(ConfigError::new(format!("at {}: {}", self.pos, msg)))
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        self.skip_ws();
+        let found = self.src[self.pos..].starts_with(s);
+        if found {
+            self.pos += s.len();
+        }
+        found
+    }
+
+    fn value(&mut self) -> Result<Value, ConfigError> {
+        self.skip_ws();
+        match self.peek() {
+            Some(b'{' | b'[') if self.depth == MAX_DEPTH => {
+                self.error(&format!("nested deeper than {}", MAX_DEPTH))
+            }
+            Some(b'{') => self.nested(Self::object),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'-' | b'0'..=b'9') => Ok(self.number()),
+            _ if self.eat("null") => Ok(Value::Null),
+            _ if self.eat("true") => Ok(Value::Bool(true)),
+            _ if self.eat("false") => Ok(Value::Bool(false)),
+            _ => self.error("expected a value"),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        f: fn(&mut Self) -> Result<Value, ConfigError>,
+    ) -> Result<Value, ConfigError> {
+        self.depth += 1;
+        let value = f(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn object(&mut self) -> Result<Value, ConfigError> {
+        self.pos += 1;
+        let mut obj = Vec::new();
+        if self.eat("}") {
+            return Ok(Value::Object(obj));
+        }
+
+        loop {
+            self.skip_ws();
+            if self.peek() != Some(b'"') {
+                return self.error("expected a key");
+            }
+            let key = self.string()?;
+            if !self.eat(":") {
+                return self.error("expected `:`");
+            }
+            obj.push((key, self.value()?));
+
+            if self.eat("}") {
+                return Ok(Value::Object(obj));
+            }
+            if !self.eat(",") {
+                return self.error("expected `,` or `}`");
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, ConfigError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        if self.eat("]") {
+            return Ok(Value::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+            if self.eat("]") {
+                return Ok(Value::Array(items));
+            }
+            if !self.eat(",") {
+                return self.error("expected `,` or `]`");
+            }
+        }
+    }
+
+    fn number(&mut self) -> Value {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|b| b.is_ascii_digit() || b"+-.eE".contains(&b))
+        {
+            self.pos += 1;
+        }
+        Value::Number(self.src[start..self.pos].to_string())
+    }
+
+    fn string(&mut self) -> Result<String, ConfigError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut s = String::new();
+
+        loop {
+            let Some(c) = self.src[self.pos..].chars().next() else {
+                self.pos = start;
+                return self.error("unclosed string");
+            };
+            self.pos += c.len_utf8();
+
+            match c {
+                '"' => return Ok(s),
+                '\\' => {
+                    let esc = self.peek();
+                    self.pos += 1;
+                    match esc {
+                        Some(b'"') => s.push('"'),
+                        Some(b'\\') => s.push('\\'),
+                        Some(b'/') => s.push('/'),
+                        Some(b'b') => s.push('\u{8}'),
+                        Some(b'f') => s.push('\u{c}'),
+                        Some(b'n') => s.push('\n'),
+                        Some(b'r') => s.push('\r'),
+                        Some(b't') => s.push('\t'),
+                        Some(b'u') => s.push(self.unicode()?),
+                        _ => return self.error("bad escape"),
+                    }
+                }
+                c => s.push(c),
+            }
+        }
+    }
+
+    /// The `XXXX` of `\uXXXX`, with a following low surrogate if needed
+    fn unicode(&mut self) -> Result<char, ConfigError> {
+        let hex4 = |p: &mut Self| {
+            let code = p
+                .src
+                .get(p.pos..p.pos + 4)
+                .and_then(|h| u32::from_str_radix(h, 16).ok());
+            p.pos += 4;
+            code.map_or_else(|| p.error("bad \\u escape"), Ok)
+        };
+
+        let high = hex4(self)?;
+        let code = if (0xd800..0xdc00).contains(&high) && self.eat("\\u") {
+            let low = hex4(self)?;
+            0x10000 + ((high - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff)
+        } else {
+            high
+        };
+        char::from_u32(code).map_or_else(|| self.error("bad \\u escape"), Ok)
+    }
+}
+
+macro_rules! config_number {
+    ($($t:ty),*) => {$(
+        impl Config for $t {
+            fn to_value(&self) -> Value {
+                Value::Number(format!("{:?}", self))
+            }
+
+            fn from_value(value: &Value) -> Result<Self, ConfigError> {
+                match value {
+                    Value::Number(n) => n.parse().map_err(|_| {
+                        ConfigError::new(format!("{} is not a {}", n, stringify!($t)))
+                    }),
+                    _ => Err(ConfigError::new(concat!("expected a ", stringify!($t)))),
+                }
+            }
+        }
+    )*};
+}
+
+config_number!(u8, u32, i32, u64, i64, i128);
+
+impl Config for f64 {
+    fn to_value(&self) -> Value {
+        Value::Number(format!("{:?}", self))
+    }
+
+    fn from_value(value: &Value) -> Result<Self, ConfigError> {
+        match value {
+            Value::Number(n) => match n.parse::<f64>() {
+                Ok(x) if x.is_finite() => Ok(x),
+                _ => Err(ConfigError::new(format!("{} is not a finite f64", n))),
+            },
+            _ => Err(ConfigError::new("expected a f64")),
+        }
+    }
+}
+
+impl Config for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+
+    fn from_value(value: &Value) -> Result<Self, ConfigError> {
+        match value {
+            Value::Bool(b) => Ok(*b),
+            _ => Err(ConfigError::new("expected true or false")),
+        }
+    }
+}
+
+impl Config for String {
+    fn to_value(&self) -> Value {
+        Value::String(self.clone())
+    }
+
+    fn from_value(value: &Value) -> Result<Self, ConfigError> {
+        match value {
+            Value::String(s) => Ok(s.clone()),
+            _ => Err(ConfigError::new("expected a string")),
+        }
+    }
+}
+
+impl<T: Config> Config for Vec<T> {
+    fn to_value(&self) -> Value {
+        Value::Array(self.iter().map(T::to_value).collect())
+    }
+
+    fn from_value(value: &Value) -> Result<Self, ConfigError> {
+        match value {
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| T::from_value(item).map_err(|e| e.at(format_args!("[{}]", i))))
+                .collect(),
+            _ => Err(ConfigError::new("expected an array")),
+        }
+    }
+}
+
+/// `[start, end]`
+impl<T: Config> Config for RangeInclusive<T> {
+    fn to_value(&self) -> Value {
+        Value::Array(vec![self.start().to_value(), self.end().to_value()])
+    }
+
+    fn from_value(value: &Value) -> Result<Self, ConfigError> {
+        match value.items() {
+            [start, end] => Ok(T::from_value(start)?..=T::from_value(end)?),
+            _ => Err(ConfigError::new("expected a range [start, end]")),
+        }
+    }
+}
+
+/// Whole milliseconds
+impl Config for Duration {
+    fn to_value(&self) -> Value {
+        (self.as_millis() as u64).to_value()
+    }
+
+    fn from_value(value: &Value) -> Result<Self, ConfigError> {
+        u64::from_value(value).map(Duration::from_millis)
+    }
+}
+
+impl Config for DateTime {
+    fn to_value(&self) -> Value {
+        Value::Object(vec![
+            ("unix_millis".to_string(), self.unix_millis().to_value()),
+            ("offset".to_string(), self.offset().to_value()),
+        ])
+    }
+
+    fn from_value(value: &Value) -> Result<Self, ConfigError> {
+        let at = DateTime::from_unix_millis(value.field("unix_millis")?);
+        Ok(at.with_offset(value.opt_field("offset")?.unwrap_or(0)))
+    }
+}
+
+/// Decodes a unit-like enum from its name
+macro_rules! config_names {
+    ($t:ty { $($variant:path => $name:literal),* $(,)? }) => {
+        impl Config for $t {
+            fn to_value(&self) -> Value {
+                match self {
+                    $($variant => Value::String($name.to_string()),)*
+                }
+            }
+
+            fn from_value(value: &Value) -> Result<Self, ConfigError> {
+                match value {
+                    $(Value::String(s) if s == $name => Ok($variant),)*
+                    _ => Err(ConfigError::new(concat!("expected one of", $(" ", $name),*))),
+                }
+            }
+        }
+    };
+}
+
+config_names!(Case {
+    Case::Lower => "lower",
+    Case::Upper => "upper",
+    Case::Mixed => "mixed",
+});
+
+config_names!(DurationFormat {
+    DurationFormat::Iso8601 => "iso8601",
+    DurationFormat::Human => "human",
+    DurationFormat::Secs => "secs",
+    DurationFormat::Millis => "millis",
+});
+
+impl Config for TimeFormat {
+    fn to_value(&self) -> Value {
+        let name = match self {
+            TimeFormat::Rfc3339 => "rfc3339",
+            TimeFormat::Unix => "unix",
+            TimeFormat::UnixMillis => "unix_millis",
+            TimeFormat::Chinese => "chinese",
+            TimeFormat::ChineseDateTime => "chinese_datetime",
+            TimeFormat::Pattern(pattern) => return Value::tag("pattern", pattern.to_value()),
+        };
+        Value::String(name.to_string())
+    }
+
+    fn from_value(value: &Value) -> Result<Self, ConfigError> {
+        Ok(match value.tagged()? {
+            ("rfc3339", None) => TimeFormat::Rfc3339,
+            ("unix", None) => TimeFormat::Unix,
+            ("unix_millis", None) => TimeFormat::UnixMillis,
+            ("chinese", None) => TimeFormat::Chinese,
+            ("chinese_datetime", None) => TimeFormat::ChineseDateTime,
+            ("pattern", Some(pattern)) => TimeFormat::Pattern(String::from_value(pattern)?),
+            (name, _) => return Err(ConfigError::new(format!("unknown time format `{}`", name))),
+        })
+    }
+}
+
+impl Config for Others {
+    fn to_value(&self) -> Value {
+        let obj = |fields: Vec<(&str, Value)>| {
+            Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v))
+                    .collect(),
+            )
+        };
+
+        let (tag, value) = match self {
+            Others::Uuid4 => return Value::String("uuid4".to_string()),
+            Others::Uuid7 => return Value::String("uuid7".to_string()),
+            Others::Ulid => return Value::String("ulid".to_string()),
+            Others::Lowers(rg) => ("lowers", rg.to_value()),
+            Others::Uppers(rg) => ("uppers", rg.to_value()),
+            Others::LowersAndUppers(rg) => ("lowers_and_uppers", rg.to_value()),
+            Others::Digits(rg) => ("digits", rg.to_value()),
+            Others::DigitsNonZero(rg) => ("digits_nonzero", rg.to_value()),
+            Others::Hex(rg) => ("hex", rg.to_value()),
+            Others::Int(rg) => ("int", rg.to_value()),
+            Others::Uint(rg) => ("uint", rg.to_value()),
+            Others::Int128(rg) => ("int128", rg.to_value()),
+            Others::Uuid7At(at) => ("uuid7_at", at.to_value()),
+            Others::UlidAt(at) => ("ulid_at", at.to_value()),
+            Others::NanoId(rg, alphabet) => (
+                "nanoid",
+                obj(vec![
+                    ("len", rg.to_value()),
+                    ("alphabet", alphabet.to_value()),
+                ]),
+            ),
+            Others::Decimal(rg, precision) => (
+                "decimal",
+                obj(vec![
+                    ("range", rg.to_value()),
+                    ("precision", precision.to_value()),
+                ]),
+            ),
+            Others::DateTime(rg, fmt) => (
+                "datetime",
+                obj(vec![("range", rg.to_value()), ("format", fmt.to_value())]),
+            ),
+            Others::Duration(rg, fmt) => (
+                "duration",
+                obj(vec![("range", rg.to_value()), ("format", fmt.to_value())]),
+            ),
+        };
+        Value::tag(tag, value)
+    }
+
+    fn from_value(value: &Value) -> Result<Self, ConfigError> {
+        let (tag, inner) = value.tagged()?;
+        let Some(v) = inner else {
+            return match tag {
+                "uuid4" => Ok(Others::Uuid4),
+                "uuid7" => Ok(Others::Uuid7),
+                "ulid" => Ok(Others::Ulid),
+                _ => Err(ConfigError::new(format!("unknown others `{}`", tag))),
+            };
+        };
+
+        let others = match tag {
+            "lowers" => Others::Lowers(Config::from_value(v)?),
+            "uppers" => Others::Uppers(Config::from_value(v)?),
+            "lowers_and_uppers" => Others::LowersAndUppers(Config::from_value(v)?),
+            "digits" => Others::Digits(Config::from_value(v)?),
+            "digits_nonzero" => Others::DigitsNonZero(Config::from_value(v)?),
+            "hex" => Others::Hex(Config::from_value(v)?),
+            "int" => Others::Int(Config::from_value(v)?),
+            "uint" => Others::Uint(Config::from_value(v)?),
+            "int128" => Others::Int128(Config::from_value(v)?),
+            "uuid7_at" => Others::Uuid7At(Config::from_value(v)?),
+            "ulid_at" => Others::UlidAt(Config::from_value(v)?),
+            "nanoid" => Others::NanoId(v.field("len")?, v.field("alphabet")?),
+            "decimal" => Others::Decimal(v.field("range")?, v.field("precision")?),
+            "datetime" => Others::DateTime(v.field("range")?, v.field("format")?),
+            "duration" => Others::Duration(v.field("range")?, v.field("format")?),
+            _ => return Err(ConfigError::new(format!("unknown others `{}`", tag))),
+        };
+        Ok(others)
+    }
+}
+
+impl Config for OwnedMode {
+    fn to_value(&self) -> Value {
+        let obj = |fields: Vec<(&str, Value)>| {
+            Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v))
+                    .collect(),
+            )
+        };
+
+        let name = match self {
+            Mode::Noun => "noun",
+            Mode::Verb => "verb",
+            Mode::Pred => "pred",
+            Mode::Adj => "adj",
+            Mode::Adverb => "adverb",
+            Mode::SVO => "svo",
+            Mode::SLP => "slp",
+            Mode::Rand => "rand",
+            Mode::Diy(items) => return Value::tag("diy", items.to_vec().to_value()),
+            Mode::DiyOwned(items) => return Value::tag("diy", items.to_vec().to_value()),
+            Mode::Others(others) => return Value::tag("others", others.to_value()),
+            Mode::ASVO(sep) => return Value::tag("asvo", sep.to_value()),
+            Mode::SVOA(sep) => return Value::tag("svoa", sep.to_value()),
+            Mode::ASLP(sep) => return Value::tag("aslp", sep.to_value()),
+            Mode::SLPA(sep) => return Value::tag("slpa", sep.to_value()),
+            Mode::Concat(modes) => return Value::tag("concat", modes.to_value()),
+            Mode::OneOf(modes) => return Value::tag("one_of", modes.to_value()),
+            Mode::Optional(mode, percent) => {
+                let fields = vec![("mode", mode.to_value()), ("percent", percent.to_value())];
+                return Value::tag("optional", obj(fields));
+            }
+            Mode::Repeat(mode, times, sep) => {
+                let fields = vec![
+                    ("mode", mode.to_value()),
+                    ("times", times.to_value()),
+                    ("sep", sep.to_value()),
+                ];
+                return Value::tag("repeat", obj(fields));
+            }
+        };
+        Value::String(name.to_string())
+    }
+
+    fn from_value(value: &Value) -> Result<Self, ConfigError> {
+        let sep = |v: Option<&Value>| v.map_or_else(|| Ok(" ".to_string()), String::from_value);
+
+        let mode = match value.tagged()? {
+            ("noun", None) => Mode::Noun,
+            ("verb", None) => Mode::Verb,
+            ("pred", None) => Mode::Pred,
+            ("adj", None) => Mode::Adj,
+            ("adverb", None) => Mode::Adverb,
+            ("svo", None) => Mode::SVO,
+            ("slp", None) => Mode::SLP,
+            ("rand", None) => Mode::Rand,
+            ("diy", Some(items)) => Mode::diy_owned(Vec::<String>::from_value(items)?),
+            ("others", Some(others)) => Mode::Others(Config::from_value(others)?),
+            ("asvo", v) => Mode::ASVO(sep(v)?),
+            ("svoa", v) => Mode::SVOA(sep(v)?),
+            ("aslp", v) => Mode::ASLP(sep(v)?),
+            ("slpa", v) => Mode::SLPA(sep(v)?),
+            ("concat", Some(modes)) => Mode::Concat(Config::from_value(modes)?),
+            ("one_of", Some(modes)) => Mode::OneOf(Config::from_value(modes)?),
+            ("optional", Some(v)) => {
+                Mode::Optional(Box::new(v.field("mode")?), v.field("percent")?)
+            }
+            ("repeat", Some(v)) => Mode::Repeat(
+                Box::new(v.field("mode")?),
+                v.field("times")?,
+                sep(v.get("sep"))?,
+            ),
+            (name, _) => return Err(ConfigError::new(format!("unknown mode `{}`", name))),
+        };
+        Ok(mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Recipe;
+    use crate::dist::Dist;
+    use crate::fmt::json::Json;
+    use crate::time::DateTime;
+    use crate::time::TimeFormat;
+    use crate::Mode;
+    use crate::Others;
+    use crate::Rg;
+
+    #[test]
+    fn round_trip() {
+        let mut rg = Rg::with_dec("{", "}");
+        rg.rand_pool::<&str>(&[(Mode::Others(Others::Hex(4..=4)), 1), (Mode::Rand, 2)]);
+        let modes = [
+            Mode::Noun,
+            Mode::Diy(&["a \"quoted\"", "b\n"][..]),
+            Mode::Others(Others::decimal(-1.0..=1.0, 2)),
+            Mode::Others(Others::DateTime(
+                DateTime::ymd(2026, 1, 1)..=DateTime::ymd(2026, 12, 31).with_offset(480),
+                TimeFormat::Pattern("%Y/%m/%d".to_string()),
+            )),
+            Mode::Others(Others::Uint(1..=9)),
+            Mode::ASVO("，"),
+            Mode::Rand,
+        ];
+        let mut recipe = Recipe::from_combine(&rg, &modes, &[",", ";"]);
+        recipe.seed = Some(42);
+        recipe.json = Some(
+            Json::new()
+                .field_cnt(1..=3)
+                .max_level(1)
+                .numeric_dist(Dist::zipf(5, 1.1))
+                .leaf(Others::Ulid),
+        );
+
+        let text = recipe.to_string();
+        println!("{}", text);
+        let loaded: Recipe = text.parse().unwrap();
+        assert_eq!(loaded.to_string(), text);
+        assert_eq!(
+            loaded.modes()[1],
+            Mode::diy_owned(["a \"quoted\"".to_string(), "b\n".to_string()])
+        );
+
+        loaded.reseed();
+        let a = loaded.combine();
+        recipe.reseed();
+        assert_eq!(a, rg.combine(&modes, &[",", ";"]));
+    }
+
+    #[test]
+    fn hand_written() {
+        let recipe: Recipe = r#"{
+            "version": 1,
+            "modes": ["verb", {"others": {"digits": [2, 2]}}, {"aslp": "·"}],
+            "json": {"string_case": "upper"}
+        }"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(recipe.modes().len(), 3);
+        assert_eq!(recipe.modes()[2], Mode::ASLP("·".to_string()));
+
+        let nested: Recipe = r#"{
+            "version": 1,
+            "modes": [{"concat": [
+                {"repeat": {"mode": "noun", "times": [1, 3], "sep": "-"}},
+                {"optional": {"mode": {"one_of": ["verb", "adj"]}, "percent": 30}}
+            ]}]
+        }"#
+        .parse()
+        .unwrap();
+        let text = nested.to_string();
+        assert_eq!(text.parse::<Recipe>().unwrap().to_string(), text);
+        assert!(!nested.combine().is_empty());
+        assert!(recipe.seed.is_none() && recipe.json.is_some());
+
+        let errors = [
+            (r#"{"version": 2}"#, "unsupported version 2, expected 1"),
+            (
+                r#"{"version": 1, "modes": ["noun", "nouns"]}"#,
+                "modes[1]: unknown mode `nouns`",
+            ),
+            (
+                r#"{"version": 1, "json": {"field_cnt": [1]}}"#,
+                "json: field_cnt: expected a range [start, end]",
+            ),
+            (r#"{"version": 1"#, "at 13: expected `,` or `}`"),
+            (
+                r#"{"version": 1, "modes": ["noun", {"diy": []}]}"#,
+                "modes[1]: empty Mode::Diy",
+            ),
+            (
+                r#"{"version": 1, "rg": {"rand_pool": [{"mode": {"one_of": []}, "weight": 1}]}}"#,
+                "rg: rand_pool[0]: empty Mode::OneOf",
+            ),
+            (
+                r#"{"version": 1, "json": {"numeric_dist": {"normal": {"mean": 1e999, "std_dev": 1}}}}"#,
+                "json: numeric_dist: mean: 1e999 is not a finite f64",
+            ),
+        ];
+        for (src, msg) in errors {
+            assert_eq!(src.parse::<Recipe>().unwrap_err().msg, msg);
+        }
+
+        let deep = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        let err = deep.parse::<Recipe>().unwrap_err();
+        assert_eq!(err.msg, "at 128: nested deeper than 128");
+    }
+
+    #[test]
+    fn non_finite() {
+        let mut recipe = Recipe::new();
+        recipe.json = Some(Json::new().numeric_dist(Dist::normal(f64::NAN, 1.0)));
+
+        let err = recipe.try_to_string().unwrap_err();
+        assert_eq!(
+            err.msg,
+            "json: numeric_dist: normal: mean: NaN is not a finite number"
+        );
+
+        recipe.json = Some(Json::new().numeric_dist(Dist::normal(0.5, 1.0)));
+        let text = recipe.try_to_string().unwrap();
+        assert_eq!(text.parse::<Recipe>().unwrap().to_string(), text);
+    }
+}
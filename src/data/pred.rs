@@ -1,11 +1,11 @@
 const ADJ_LEN: usize = 69;
 
 pub(crate) fn nouns() -> &'static [&'static str] {
-    unsafe { PREDS.get_unchecked(ADJ_LEN..) }
+    &PREDS[ADJ_LEN..]
 }
 
 pub(crate) fn adjs() -> &'static [&'static str] {
-    unsafe { PREDS.get_unchecked(..ADJ_LEN) }
+    &PREDS[..ADJ_LEN]
 }
 
 pub(crate) const PREDS: &[&str] = &[
@@ -0,0 +1,344 @@
+use crate::combinator::Generator;
+use crate::config::Config;
+use crate::config::ConfigError;
+use crate::config::Value;
+use crate::util;
+use crate::Rg;
+use std::f64::consts::PI;
+use std::fmt::Write;
+
+/// A parameterized distribution of `f64`, sampled through the crate rng.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dist {
+    kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Uniform {
+        low: f64,
+        high: f64,
+    },
+    Normal {
+        mean: f64,
+        std_dev: f64,
+    },
+    LogNormal {
+        mu: f64,
+        sigma: f64,
+    },
+    Exponential {
+        lambda: f64,
+    },
+    Poisson {
+        lambda: f64,
+    },
+    /// `cdf` is cumulative and ends with 1.0, `weights` as given
+    Discrete {
+        values: Vec<f64>,
+        weights: Vec<f64>,
+        cdf: Vec<f64>,
+    },
+}
+
+impl Dist {
+    pub fn uniform(low: f64, high: f64) -> Self {
+        assert!(low <= high, "uniform: low > high");
+        Self::new(Kind::Uniform { low, high })
+    }
+
+    pub fn normal(mean: f64, std_dev: f64) -> Self {
+        assert!(std_dev >= 0.0, "normal: negative std_dev");
+        Self::new(Kind::Normal { mean, std_dev })
+    }
+
+    /// `exp` of a normal with `mu` and `sigma`, e.g. order amounts
+    pub fn log_normal(mu: f64, sigma: f64) -> Self {
+        assert!(sigma >= 0.0, "log_normal: negative sigma");
+        Self::new(Kind::LogNormal { mu, sigma })
+    }
+
+    /// Mean `1 / lambda`, e.g. session lengths or gaps between events
+    pub fn exponential(lambda: f64) -> Self {
+        assert!(lambda > 0.0, "exponential: lambda must be positive");
+        Self::new(Kind::Exponential { lambda })
+    }
+
+    /// Counts with mean `lambda`, approximated by a normal above 30
+    pub fn poisson(lambda: f64) -> Self {
+        assert!(lambda > 0.0, "poisson: lambda must be positive");
+        Self::new(Kind::Poisson { lambda })
+    }
+
+    /// Ranks `1..=n`, rank `k` weighted `1 / k^s`. Keeps a table of `n` entries.
+    pub fn zipf(n: u64, s: f64) -> Self {
+        assert!(n > 0, "zipf: n must be positive");
+        let pairs: Vec<_> = (1..=n).map(|k| (k as f64, (k as f64).powf(-s))).collect();
+        Self::discrete(&pairs)
+    }
+
+    /// `(value, weight)` pairs, weights need not sum to 1
+    pub fn discrete(pairs: &[(f64, f64)]) -> Self {
+        let total: f64 = pairs.iter().map(|(_, w)| w).sum();
+        assert!(
+            total > 0.0 && pairs.iter().all(|(_, w)| *w >= 0.0),
+            "discrete: weights must be non-negative and not all zero"
+        );
+
+        let mut acc = 0.0;
+        let cdf = pairs
+            .iter()
+            .map(|(_, w)| {
+                acc += w / total;
+                acc
+            })
+            .collect();
+
+        Self::new(Kind::Discrete {
+            values: pairs.iter().map(|(v, _)| *v).collect(),
+            weights: pairs.iter().map(|(_, w)| *w).collect(),
+            cdf,
+        })
+    }
+
+    fn new(kind: Kind) -> Self {
+        Self { kind }
+    }
+
+    pub fn sample(&self) -> f64 {
+        match &self.kind {
+            Kind::Uniform { low, high } => util::rand_range(*low..=*high),
+            Kind::Normal { mean, std_dev } => mean + std_dev * standard_normal(),
+            Kind::LogNormal { mu, sigma } => (mu + sigma * standard_normal()).exp(),
+            Kind::Exponential { lambda } => -open01().ln() / lambda,
+            Kind::Poisson { lambda } if *lambda < 30.0 => {
+                // Knuth
+                let limit = (-lambda).exp();
+                let mut k = 0.0;
+                let mut p = open01();
+
+                while p > limit {
+                    k += 1.0;
+                    p *= open01();
+                }
+                k
+            }
+            Kind::Poisson { lambda } => (lambda + lambda.sqrt() * standard_normal())
+                .round()
+                .max(0.0),
+            Kind::Discrete { values, cdf, .. } => {
+                let u: f64 = util::rand_range(0.0..1.0);
+                let idx = cdf.partition_point(|c| *c <= u).min(values.len() - 1);
+                values[idx]
+            }
+        }
+    }
+
+    /// A [`Generator`] writing samples with `precision` decimals.
+    pub fn generator(self, precision: u32) -> Sample {
+        Sample {
+            dist: self,
+            precision,
+        }
+    }
+}
+
+/// (0, 1], safe for `ln`
+fn open01() -> f64 {
+    1.0 - util::rand_range(0.0..1.0)
+}
+
+/// Box-Muller
+fn standard_normal() -> f64 {
+    let u: f64 = util::rand_range(0.0..1.0);
+    (-2.0 * open01().ln()).sqrt() * (2.0 * PI * u).cos()
+}
+
+pub(crate) fn push_sample(buf: &mut String, x: f64, precision: u32) {
+    if precision == 0 {
+        let _ = write!(buf, "{}", x.round() as i64);
+    } else {
+        let _ = write!(buf, "{:.*}", precision as usize, x);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Sample {
+    dist: Dist,
+    precision: u32,
+}
+
+impl Generator for Sample {
+    fn once(&mut self, mut buf: String) -> String {
+        push_sample(&mut buf, self.dist.sample(), self.precision);
+        buf
+    }
+}
+
+/// `{"normal": {"mean": 0.0, "std_dev": 1.0}}`, discrete as `[[value, weight], ...]`
+impl Config for Dist {
+    fn to_value(&self) -> Value {
+        let params = |params: &[(&str, f64)]| {
+            Value::Object(
+                params
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_value()))
+                    .collect(),
+            )
+        };
+
+        let (tag, value) = match &self.kind {
+            Kind::Uniform { low, high } => ("uniform", params(&[("low", *low), ("high", *high)])),
+            Kind::Normal { mean, std_dev } => {
+                ("normal", params(&[("mean", *mean), ("std_dev", *std_dev)]))
+            }
+            Kind::LogNormal { mu, sigma } => {
+                ("log_normal", params(&[("mu", *mu), ("sigma", *sigma)]))
+            }
+            Kind::Exponential { lambda } => ("exponential", params(&[("lambda", *lambda)])),
+            Kind::Poisson { lambda } => ("poisson", params(&[("lambda", *lambda)])),
+            Kind::Discrete {
+                values, weights, ..
+            } => (
+                "discrete",
+                Value::Array(
+                    values
+                        .iter()
+                        .zip(weights)
+                        .map(|(v, w)| vec![*v, *w].to_value())
+                        .collect(),
+                ),
+            ),
+        };
+        Value::Object(vec![(tag.to_string(), value)])
+    }
+
+    fn from_value(value: &Value) -> Result<Self, ConfigError> {
+        let (tag, v) = match value {
+            Value::Object(obj) if obj.len() == 1 => (&obj[0].0[..], &obj[0].1),
+            _ => return Err(ConfigError::new("expected a one-key object")),
+        };
+        let positive = |x: f64, name: &str| {
+            if x > 0.0 {
+                Ok(x)
+            } else {
+                Err(ConfigError::new(format!("{} must be positive", name)))
+            }
+        };
+        let non_negative = |x: f64, name: &str| {
+            if x >= 0.0 {
+                Ok(x)
+            } else {
+                Err(ConfigError::new(format!("{} must not be negative", name)))
+            }
+        };
+
+        Ok(match tag {
+            "uniform" => {
+                let (low, high): (f64, f64) = (v.field("low")?, v.field("high")?);
+                if low > high {
+                    return Err(ConfigError::new("low > high"));
+                }
+                Dist::uniform(low, high)
+            }
+            "normal" => Dist::normal(
+                v.field("mean")?,
+                non_negative(v.field("std_dev")?, "std_dev")?,
+            ),
+            "log_normal" => {
+                Dist::log_normal(v.field("mu")?, non_negative(v.field("sigma")?, "sigma")?)
+            }
+            "exponential" => Dist::exponential(positive(v.field("lambda")?, "lambda")?),
+            "poisson" => Dist::poisson(positive(v.field("lambda")?, "lambda")?),
+            "discrete" => {
+                let pairs: Vec<Vec<f64>> = Config::from_value(v)?;
+                let pairs = pairs
+                    .iter()
+                    .map(|pair| match pair[..] {
+                        [value, weight] if weight >= 0.0 => Ok((value, weight)),
+                        _ => Err(ConfigError::new("expected [value, weight >= 0]")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if !pairs.iter().any(|(_, w)| *w > 0.0) {
+                    return Err(ConfigError::new("weights are all zero"));
+                }
+                Dist::discrete(&pairs)
+            }
+            _ => return Err(ConfigError::new(format!("unknown dist `{}`", tag))),
+        })
+    }
+}
+
+impl<'a> Rg<'a> {
+    pub fn sample(&self, dist: &Dist) -> f64 {
+        dist.sample()
+    }
+
+    /// Rounded to `precision` decimals, `0` writes an integer
+    pub fn sample_with_buf(&self, mut buf: String, dist: &Dist, precision: u32) -> String {
+        self.push_left(&mut buf, true);
+        push_sample(&mut buf, dist.sample(), precision);
+        self.push_right(&mut buf, true);
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dist;
+    use crate::combinator::Generator;
+    use crate::config::Config;
+    use crate::config::Value;
+    use crate::Rg;
+
+    fn mean(dist: &Dist, n: usize) -> f64 {
+        (0..n).map(|_| dist.sample()).sum::<f64>() / n as f64
+    }
+
+    #[test]
+    fn means() {
+        let n = 20_000;
+
+        assert!((mean(&Dist::normal(10.0, 2.0), n) - 10.0).abs() < 0.2);
+        assert!((mean(&Dist::exponential(0.5), n) - 2.0).abs() < 0.2);
+        assert!((mean(&Dist::poisson(4.0), n) - 4.0).abs() < 0.2);
+        assert!((mean(&Dist::poisson(100.0), n) - 100.0).abs() < 1.0);
+        assert!((mean(&Dist::log_normal(0.0, 0.5), n) - 0.125f64.exp()).abs() < 0.1);
+    }
+
+    #[test]
+    fn discrete() {
+        let zipf = Dist::zipf(10, 1.0);
+        let ones = (0..10_000).filter(|_| zipf.sample() == 1.0).count();
+        // 1 / H(10) ~ 0.34
+        assert!((3000..3800).contains(&ones), "{}", ones);
+
+        let d = Dist::discrete(&[(7.0, 1.0), (8.0, 0.0)]);
+        assert!((0..100).all(|_| d.sample() == 7.0));
+    }
+
+    #[test]
+    fn output() {
+        let rg = Rg::with_dec("[", "]");
+        let amount = Dist::log_normal(3.0, 1.0);
+
+        let res = rg.sample_with_buf(String::new(), &amount, 2);
+        assert!(res.starts_with('[') && res.ends_with(']'));
+        assert_eq!(res.split('.').nth(1).unwrap().len(), 3);
+
+        let res = Dist::uniform(5.0, 5.0).generator(0).generate();
+        assert_eq!(res, "5");
+    }
+
+    #[test]
+    fn config() {
+        let parse = |src: &str| Dist::from_value(&Value::parse(src).unwrap());
+
+        let dist = parse(r#"{"normal": {"mean": 1, "std_dev": 0.5}}"#).unwrap();
+        assert_eq!(dist.to_value(), Dist::normal(1.0, 0.5).to_value());
+        let err = parse(r#"{"normal": {"mean": 1, "std_dev": -0.5}}"#).unwrap_err();
+        assert_eq!(err.msg, "std_dev must not be negative");
+        let err = parse(r#"{"log_normal": {"mu": 1, "sigma": -1}}"#).unwrap_err();
+        assert_eq!(err.msg, "sigma must not be negative");
+    }
+}
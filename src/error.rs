@@ -0,0 +1,76 @@
+use crate::combinator::dsl::ParseError;
+use crate::config::ConfigError;
+use std::fmt;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+
+/// Why a `try_` function refused a configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// nothing to pick from, e.g. an empty `Mode::Diy`
+    Empty(&'static str),
+    /// a range starting after its end, e.g. `Digits(3..=1)`
+    EmptyRange(String),
+    /// any other value out of its domain
+    Invalid(String),
+    Parse(ParseError),
+    Config(ConfigError),
+    /// a `unique` generator ran out of new outputs
+    Exhausted,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Empty(what) => write!(f, "empty {}", what),
+            Error::EmptyRange(rg) => write!(f, "empty range {}", rg),
+            Error::Invalid(msg) => f.write_str(msg),
+            Error::Parse(e) => write!(f, "parse error {}", e),
+            Error::Config(e) => write!(f, "config error: {}", e),
+            Error::Exhausted => f.write_str("no new outputs left"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(e) => Some(e),
+            Error::Config(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+impl From<ConfigError> for Error {
+    fn from(e: ConfigError) -> Self {
+        Error::Config(e)
+    }
+}
+
+/// `Err` if `rg` has nothing in it, `what` names it in the message
+pub(crate) fn check_range<T: PartialOrd + Debug>(what: &str, rg: &RangeInclusive<T>) -> Result<()> {
+    // also catches NaN ends
+    if rg.start() <= rg.end() {
+        Ok(())
+    } else {
+        Err(Error::EmptyRange(format!("{} {:?}", what, rg)))
+    }
+}
+
+/// `Err` unless `p` is a probability in `0..=1`
+pub(crate) fn check_probability(p: f64) -> Result<()> {
+    if (0.0..=1.0).contains(&p) {
+        Ok(())
+    } else {
+        Err(Error::Invalid(format!("probability {} is out of 0..=1", p)))
+    }
+}
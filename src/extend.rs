@@ -1,7 +1,13 @@
+use crate::error;
+use crate::error::check_range;
 use crate::util;
 use crate::Mode;
 use crate::Others;
 use crate::Rg;
+use rand::distributions::uniform::SampleUniform;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Write;
 use std::ops::RangeInclusive;
 
 #[derive(Debug, Clone, Copy)]
@@ -113,6 +119,165 @@ impl<'a> Rg<'a> {
     ) -> String {
         self.float_with_buf(String::new(), int_rg, float_rg, negative)
     }
+
+    /// A value in `rg`, unlike [`Rg::numberic`] which picks a digit count
+    pub fn number<T>(&self, rg: RangeInclusive<T>) -> T
+    where
+        T: SampleUniform + PartialOrd,
+    {
+        util::rand_range(rg)
+    }
+
+    pub fn number_with_buf<T>(&self, mut buf: String, rg: RangeInclusive<T>) -> String
+    where
+        T: SampleUniform + PartialOrd + Display,
+    {
+        self.push_left(&mut buf, true);
+        let _ = write!(buf, "{}", self.number(rg));
+        self.push_right(&mut buf, true);
+        buf
+    }
+
+    /// A value in `rg` with at most `precision` decimals, every step of
+    /// `10^-precision` inside the range being equally likely, `precision`
+    /// is capped at 19
+    pub fn decimal(&self, rg: RangeInclusive<f64>, precision: u32) -> f64 {
+        let precision = precision.min(crate::MAX_DECIMALS);
+        let scaled = util::rand_range(scale(rg, precision));
+        scaled as f64 / 10f64.powi(precision as i32)
+    }
+
+    /// Always prints exactly `precision` decimals, e.g. `"0.10"`, `precision`
+    /// is capped at 19
+    pub fn decimal_with_buf(
+        &self,
+        mut buf: String,
+        rg: RangeInclusive<f64>,
+        precision: u32,
+    ) -> String {
+        let precision = precision.min(crate::MAX_DECIMALS);
+        self.push_left(&mut buf, true);
+        push_scaled(&mut buf, util::rand_range(scale(rg, precision)), precision);
+        self.push_right(&mut buf, true);
+        buf
+    }
+
+    pub fn try_numeric_with_buf(
+        &mut self,
+        buf: String,
+        rg: RangeInclusive<u32>,
+        negative: bool,
+        push_dec: bool,
+    ) -> error::Result<String> {
+        check_range("length", &rg)?;
+        Ok(self.numeric_with_buf(buf, rg, negative, push_dec))
+    }
+
+    pub fn try_word_with_buf(
+        &mut self,
+        buf: String,
+        rg: RangeInclusive<u32>,
+        case: Case,
+    ) -> error::Result<String> {
+        check_range("length", &rg)?;
+        Ok(self.word_with_buf(buf, rg, case))
+    }
+
+    pub fn try_float_with_buf(
+        &mut self,
+        buf: String,
+        int_rg: RangeInclusive<u32>,
+        float_rg: RangeInclusive<u32>,
+        negative: bool,
+    ) -> error::Result<String> {
+        check_range("length", &int_rg)?;
+        check_range("length", &float_rg)?;
+        Ok(self.float_with_buf(buf, int_rg, float_rg, negative))
+    }
+
+    pub fn try_number<T>(&self, rg: RangeInclusive<T>) -> error::Result<T>
+    where
+        T: SampleUniform + PartialOrd + Debug,
+    {
+        check_range("number", &rg)?;
+        Ok(self.number(rg))
+    }
+
+    pub fn try_number_with_buf<T>(
+        &self,
+        buf: String,
+        rg: RangeInclusive<T>,
+    ) -> error::Result<String>
+    where
+        T: SampleUniform + PartialOrd + Display + Debug,
+    {
+        check_range("number", &rg)?;
+        Ok(self.number_with_buf(buf, rg))
+    }
+
+    /// `Err` also when no step of `10^-precision` falls inside `rg`
+    pub fn try_decimal(&self, rg: RangeInclusive<f64>, precision: u32) -> error::Result<f64> {
+        check_decimal(&rg, precision)?;
+        Ok(self.decimal(rg, precision))
+    }
+
+    pub fn try_decimal_with_buf(
+        &self,
+        buf: String,
+        rg: RangeInclusive<f64>,
+        precision: u32,
+    ) -> error::Result<String> {
+        check_decimal(&rg, precision)?;
+        Ok(self.decimal_with_buf(buf, rg, precision))
+    }
+}
+
+fn check_decimal(rg: &RangeInclusive<f64>, precision: u32) -> error::Result<()> {
+    crate::check_decimal_precision(precision)?;
+    check_range("decimal", rg)?;
+    check_range("decimal steps", &scale(rg.clone(), precision))
+}
+
+impl Others {
+    /// `Others::decimal(0.01..=999.99, 2)` is `Others::Decimal(1..=99999, 2)`
+    pub fn decimal(rg: RangeInclusive<f64>, precision: u32) -> Self {
+        Others::Decimal(scale(rg, precision), precision)
+    }
+}
+
+fn scale(rg: RangeInclusive<f64>, precision: u32) -> RangeInclusive<i64> {
+    let factor = 10f64.powi(precision as i32);
+    let (start, end) = rg.into_inner();
+    // `0.07 * 100.0` is `7.000000000000001`, which is still the step 7
+    let snap = |x: f64| {
+        let step = x.round();
+        if (x - step).abs() <= 1e-9 * step.abs().max(1.0) {
+            step
+        } else {
+            x
+        }
+    };
+
+    snap(start * factor).ceil() as i64..=snap(end * factor).floor() as i64
+}
+
+/// Writes `scaled / 10^precision` without going through floats.
+pub(crate) fn push_scaled(buf: &mut String, scaled: i64, precision: u32) {
+    if scaled < 0 {
+        buf.push('-');
+    }
+
+    let abs = scaled.unsigned_abs();
+    // over 19 decimals every `i64` is below one
+    let (int, frac) = match 10u64.checked_pow(precision) {
+        Some(factor) => (abs / factor, abs % factor),
+        None => (0, abs),
+    };
+    let _ = write!(buf, "{}", int);
+
+    if precision > 0 {
+        let _ = write!(buf, ".{:0width$}", frac, width = precision as usize);
+    }
 }
 
 #[cfg(test)]
@@ -134,6 +299,34 @@ mod tests {
         println!("{:?}", res);
     }
 
+    #[test]
+    fn number() {
+        let rg = Rg::new();
+
+        for _ in 0..100 {
+            let age = rg.number(18..=65);
+            assert!((18..=65).contains(&age));
+
+            let price = rg.decimal(0.01..=999.99, 2);
+            assert!((0.01..=999.99).contains(&price));
+            assert_eq!(price, (price * 100.0).round() / 100.0);
+        }
+
+        assert_eq!(rg.number_with_buf(String::new(), -7i128..=-7), "-7");
+        assert_eq!(rg.decimal_with_buf(String::new(), 0.1..=0.1, 2), "0.10");
+        assert_eq!(rg.decimal_with_buf(String::new(), -2.5..=-2.5, 1), "-2.5");
+        assert_eq!(
+            rg.decimal_with_buf(String::new(), -0.05..=-0.05, 2),
+            "-0.05"
+        );
+
+        let mut buf = String::new();
+        super::push_scaled(&mut buf, -5, 21);
+        assert_eq!(buf, "-0.000000000000000000005");
+        let res = rg.decimal_with_buf(String::new(), 0.0..=0.0, 30);
+        assert_eq!(res, format!("0.{}", "0".repeat(19)));
+    }
+
     #[test]
     fn float() {
         let mut rg = Rg::with_dec("{{", "}}");
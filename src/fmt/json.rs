@@ -1,5 +1,8 @@
 use super::LevelPrinter;
-use crate::{combinator::RgBindMode, extend::Case, util, Mode, Rg};
+use crate::config::{Config, ConfigError, Value};
+use crate::error::{self, check_range};
+use crate::{dist::Dist, extend::Case, util, Mode, Others, Rg};
+use std::collections::HashSet;
 use std::ops::RangeInclusive;
 
 #[derive(Debug)]
@@ -13,6 +16,9 @@ pub struct Json {
     string_case: Case,
     level: LevelPrinter,
     max_level: i32,
+    leaves: Vec<Others>,
+    numeric_dist: Option<Dist>,
+    float_dist: Option<(Dist, u32)>,
 }
 
 impl Json {
@@ -27,6 +33,9 @@ impl Json {
             max_level: 3,
             float_int_rg: 2..=5,
             float_rg: 1..=3,
+            leaves: Vec::new(),
+            numeric_dist: None,
+            float_dist: None,
         }
     }
 
@@ -60,19 +69,65 @@ impl Json {
         self
     }
 
+    /// Integers are sampled from `dist` instead of `numeric_rg`
+    pub fn numeric_dist(mut self, dist: Dist) -> Self {
+        self.numeric_dist = Some(dist);
+        self
+    }
+
+    /// Floats are sampled from `dist` with `precision` decimals
+    pub fn float_dist(mut self, dist: Dist, precision: u32) -> Self {
+        self.float_dist = Some((dist, precision));
+        self
+    }
+
+    /// Extra value type, picked as often as a string or a number
+    pub fn leaf(mut self, others: Others) -> Self {
+        self.leaves.push(others);
+        self
+    }
+
     pub fn generate(&mut self) -> String {
         self.json_obj(String::new())
     }
 
+    /// `Err` for an empty range or leaf, which would panic when generating
+    pub fn validate(&self) -> error::Result<()> {
+        check_range("field_cnt", &self.items)?;
+        check_range("numeric_rg", &self.numeric_rg)?;
+        check_range("string_rg", &self.string_rg)?;
+        check_range("array_rg", &self.array_rg)?;
+        check_range("float_int_rg", &self.float_int_rg)?;
+        check_range("float_rg", &self.float_rg)?;
+        if let Some((_, precision)) = &self.float_dist {
+            crate::check_decimal_precision(*precision)?;
+        }
+        self.leaves.iter().try_for_each(Others::validate)
+    }
+
+    /// [`Json::generate`] after [`Json::validate`]
+    pub fn try_generate(&mut self) -> error::Result<String> {
+        self.validate()?;
+        Ok(self.generate())
+    }
+
     fn json_obj(&mut self, mut buf: String) -> String {
         self.level.upgrade();
         buf.push_str("{\n");
 
         let mut ln = "";
+        let mut keys = HashSet::new();
         for _ in 0..util::rand_range(self.items.clone()) {
+            let key = match self.key(&mut keys) {
+                Some(key) => key,
+                None => break,
+            };
+
             buf.push_str(ln);
             self.level.print(&mut buf, 0);
-            buf = self.item(buf);
+            buf.push_str(&key);
+            buf.push_str(": ");
+            buf = self.choose(buf);
             ln = ",\n";
         }
 
@@ -86,15 +141,18 @@ impl Json {
         buf
     }
 
-    fn item(&mut self, mut buf: String) -> String {
-        buf = self.string(buf);
-        buf.push_str(": ");
-        // println!("{:?}", buf);
-        self.choose(buf)
+    /// A key not in `keys` yet, `None` when `string_rg` seems to have run out of them
+    fn key(&self, keys: &mut HashSet<String>) -> Option<String> {
+        (0..100)
+            .map(|_| self.string(String::new()))
+            .find(|key| keys.insert(key.clone()))
     }
 
     /// Atomic node
     fn numeric(&self, buf: String) -> String {
+        if let Some(dist) = &self.numeric_dist {
+            return Rg::new().sample_with_buf(buf, dist, 0);
+        }
         Rg::new().numeric_with_buf(buf, self.numeric_rg.clone(), true, true)
     }
 
@@ -110,9 +168,37 @@ impl Json {
 
     /// Atomic node
     fn float(&self, buf: String) -> String {
+        if let Some((dist, precision)) = &self.float_dist {
+            return Rg::new().sample_with_buf(buf, dist, *precision);
+        }
         Rg::new().float_with_buf(buf, self.float_int_rg.clone(), self.float_rg.clone(), true)
     }
 
+    /// Atomic node
+    fn others(&self, mut buf: String, idx: usize) -> String {
+        let others = &self.leaves[idx];
+        let rg = if others.is_numeric() {
+            Rg::new()
+        } else {
+            Rg::with_dec("\"", "\"")
+        };
+
+        let _res = rg.core::<&str>(&Mode::Others(others.clone()), &mut buf, true, true);
+        buf
+    }
+
+    fn atom(&self, buf: String) -> String {
+        let choice = util::rand_range(0..4 + self.leaves.len());
+
+        match choice {
+            0 => self.string(buf),
+            1 => self.numeric(buf),
+            2 => self.boolean(buf),
+            3 => self.float(buf),
+            idx => self.others(buf, idx - 4),
+        }
+    }
+
     fn array(&mut self, mut buf: String) -> String {
         self.level.upgrade();
         buf.push_str("[\n");
@@ -135,26 +221,17 @@ impl Json {
 
     fn choose(&mut self, buf: String) -> String {
         if self.level.level() > self.max_level {
-            let choice = util::rand_range(1..=4);
-
-            match choice {
-                1 => self.string(buf),
-                2 => self.numeric(buf),
-                3 => self.boolean(buf),
-                4 => self.float(buf),
-                _ => unreachable!(),
-            }
+            self.atom(buf)
         } else {
-            let choice = util::rand_range(1..=100);
+            let choice = util::rand_range(1..=100 + 20 * self.leaves.len());
 
             match choice {
                 1..=20 => self.string(buf),
                 21..=40 => self.numeric(buf),
-                41..=60 => self.boolean(buf),
-                61..=80 => self.boolean(buf),
+                41..=80 => self.boolean(buf),
                 81..=90 => self.json_obj(buf),
                 91..=100 => self.array(buf),
-                _ => unreachable!(),
+                idx => self.others(buf, (idx - 101) / 20),
             }
         }
     }
@@ -166,9 +243,72 @@ impl Default for Json {
     }
 }
 
+/// Builder settings by their builder names, missing ones keep the defaults of [`Json::new`]
+impl Config for Json {
+    fn to_value(&self) -> Value {
+        let mut obj = vec![
+            ("field_cnt", self.items.to_value()),
+            ("numeric_rg", self.numeric_rg.to_value()),
+            ("string_rg", self.string_rg.to_value()),
+            ("array_rg", self.array_rg.to_value()),
+            ("float_int_rg", self.float_int_rg.to_value()),
+            ("float_rg", self.float_rg.to_value()),
+            ("string_case", self.string_case.to_value()),
+            ("max_level", self.max_level.to_value()),
+            ("leaves", self.leaves.to_value()),
+        ];
+        if let Some(dist) = &self.numeric_dist {
+            obj.push(("numeric_dist", dist.to_value()));
+        }
+        if let Some((dist, precision)) = &self.float_dist {
+            let float_dist = vec![
+                ("dist".to_string(), dist.to_value()),
+                ("precision".to_string(), precision.to_value()),
+            ];
+            obj.push(("float_dist", Value::Object(float_dist)));
+        }
+        Value::Object(obj.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+    }
+
+    fn from_value(value: &Value) -> Result<Self, ConfigError> {
+        if !matches!(value, Value::Object(_)) {
+            return Err(ConfigError::new("expected an object"));
+        }
+        let mut json = Json::new();
+
+        macro_rules! load {
+            ($($key:literal => $field:ident),*) => {$(
+                if let Some(v) = value.opt_field($key)? {
+                    json.$field = v;
+                }
+            )*};
+        }
+        load!(
+            "field_cnt" => items,
+            "numeric_rg" => numeric_rg,
+            "string_rg" => string_rg,
+            "array_rg" => array_rg,
+            "float_int_rg" => float_int_rg,
+            "float_rg" => float_rg,
+            "string_case" => string_case,
+            "max_level" => max_level,
+            "leaves" => leaves
+        );
+        json.numeric_dist = value.opt_field("numeric_dist")?;
+        if let Some(float_dist) = value.get("float_dist") {
+            let dist = float_dist.field("dist");
+            let precision = float_dist.field("precision");
+            json.float_dist = Some((dist?, precision?));
+        }
+        Ok(json)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Json;
+    use crate::time::{DateTime, TimeFormat};
+    use crate::Others;
 
     #[test]
     fn test() {
@@ -176,4 +316,40 @@ mod tests {
         let res = json.generate();
         println!("{}", res);
     }
+
+    #[test]
+    fn unique_keys() {
+        let mut json = Json::new().field_cnt(30..=30).string_rg(1..=1).max_level(0);
+        let res = json.generate();
+
+        let keys: Vec<_> = res
+            .lines()
+            .filter_map(|l| l.split_once(": "))
+            .map(|(k, _)| k)
+            .collect();
+        let distinct: std::collections::HashSet<_> = keys.iter().collect();
+        assert_eq!(keys.len(), distinct.len());
+    }
+
+    #[test]
+    fn leaf() {
+        let t = DateTime::ymd(2026, 10, 18);
+        let mut json = Json::new()
+            .max_level(0)
+            .leaf(Others::DateTime(t..=t, TimeFormat::Chinese));
+
+        let res = (0..20).map(|_| json.generate()).collect::<String>();
+        assert!(res.contains("\"2026年10月18日\""), "{}", res);
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn validate() {
+        let mut json = Json::new().array_rg(3..=1);
+        assert!(json.try_generate().is_err());
+
+        let mut json = Json::new().leaf(Others::Lowers(2..=0));
+        assert!(json.try_generate().is_err());
+        assert!(Json::new().max_level(0).try_generate().is_ok());
+    }
 }
@@ -1,4 +1,5 @@
 pub mod json;
+pub mod numeral;
 pub mod xml;
 
 #[derive(Debug)]
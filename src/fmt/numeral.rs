@@ -0,0 +1,178 @@
+const DIGITS: [&str; 10] = ["零", "一", "二", "三", "四", "五", "六", "七", "八", "九"];
+const UNITS: [&str; 4] = ["", "十", "百", "千"];
+const UPPER_DIGITS: [&str; 10] = ["零", "壹", "贰", "叁", "肆", "伍", "陆", "柒", "捌", "玖"];
+const UPPER_UNITS: [&str; 4] = ["", "拾", "佰", "仟"];
+const SECTIONS: [&str; 5] = ["", "万", "亿", "万亿", "亿亿"];
+
+/// 123 -> 一百二十三, 3005 -> 三千零五, 15 -> 十五, -8 -> 负八
+pub fn chinese(n: i64) -> String {
+    let mut buf = String::new();
+    if n < 0 {
+        buf.push('负');
+    }
+
+    let start = buf.len();
+    push_numeral(&mut buf, n.unsigned_abs(), &DIGITS, &UNITS);
+
+    if buf[start..].starts_with("一十") {
+        buf.replace_range(start..start + "一".len(), "");
+    }
+    buf
+}
+
+/// Financial uppercase rounded to fen:
+/// 123.45 -> 壹佰贰拾叁元肆角伍分, 100 -> 壹佰元整, 100.05 -> 壹佰元零伍分
+pub fn financial(amount: f64) -> String {
+    let cents = (amount * 100.0).round() as i64;
+    let abs = cents.unsigned_abs();
+    let (yuan, jiao, fen) = (abs / 100, abs / 10 % 10, abs % 10);
+    let mut buf = String::new();
+
+    if cents < 0 {
+        buf.push('负');
+    }
+
+    if yuan != 0 || abs == 0 {
+        push_numeral(&mut buf, yuan, &UPPER_DIGITS, &UPPER_UNITS);
+        buf.push('元');
+    }
+
+    if jiao != 0 {
+        buf.push_str(UPPER_DIGITS[jiao as usize]);
+        buf.push('角');
+    } else if fen != 0 && yuan != 0 {
+        buf.push('零');
+    }
+
+    if fen != 0 {
+        buf.push_str(UPPER_DIGITS[fen as usize]);
+        buf.push('分');
+    } else {
+        buf.push('整');
+    }
+    buf
+}
+
+/// 1234567 -> 1,234,567 with `sep` = ","
+pub fn thousands(n: i64, sep: &str) -> String {
+    let digits = n.unsigned_abs().to_string();
+    let mut buf = String::new();
+
+    if n < 0 {
+        buf.push('-');
+    }
+
+    for (i, c) in digits.chars().enumerate() {
+        if i != 0 && (digits.len() - i).is_multiple_of(3) {
+            buf.push_str(sep);
+        }
+        buf.push(c);
+    }
+    buf
+}
+
+/// 1234.5 -> ¥1,234.50 with `symbol` = "¥"
+pub fn currency(amount: f64, symbol: &str) -> String {
+    let cents = (amount * 100.0).round() as i64;
+    let abs = cents.unsigned_abs();
+    let mut buf = String::new();
+
+    if cents < 0 {
+        buf.push('-');
+    }
+    buf.push_str(symbol);
+    buf.push_str(&thousands((abs / 100) as i64, ","));
+    buf.push_str(&format!(".{:02}", abs % 100));
+    buf
+}
+
+/// Groups of four digits, one 零 for every run of zeros between non-zero digits.
+fn push_numeral(buf: &mut String, n: u64, digits: &[&str; 10], units: &[&str; 4]) {
+    if n == 0 {
+        buf.push_str(digits[0]);
+        return;
+    }
+
+    let mut sections = Vec::new();
+    let mut rest = n;
+    while rest != 0 {
+        sections.push((rest % 10000) as usize);
+        rest /= 10000;
+    }
+
+    let mut emitted = false;
+    let mut zero = false;
+
+    for (idx, &section) in sections.iter().enumerate().rev() {
+        if section == 0 {
+            zero = emitted;
+            continue;
+        }
+
+        if emitted && (zero || section < 1000) {
+            buf.push_str(digits[0]);
+        }
+        zero = false;
+
+        let mut inner_zero = false;
+        let mut inner_emitted = false;
+        for pos in (0..4).rev() {
+            let d = section / 10usize.pow(pos as u32) % 10;
+
+            if d == 0 {
+                inner_zero = inner_emitted;
+                continue;
+            }
+            if inner_zero {
+                buf.push_str(digits[0]);
+                inner_zero = false;
+            }
+            buf.push_str(digits[d]);
+            buf.push_str(units[pos]);
+            inner_emitted = true;
+        }
+
+        buf.push_str(SECTIONS[idx]);
+        emitted = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numerals() {
+        for (n, s) in [
+            (0, "零"),
+            (10, "十"),
+            (15, "十五"),
+            (110, "一百一十"),
+            (123, "一百二十三"),
+            (3005, "三千零五"),
+            (10001, "一万零一"),
+            (10100, "一万零一百"),
+            (10001000, "一千万一千"),
+            (100001000, "一亿零一千"),
+            (120000000, "一亿二千万"),
+            (-8, "负八"),
+        ] {
+            assert_eq!(chinese(n), s);
+        }
+    }
+
+    #[test]
+    fn amounts() {
+        assert_eq!(financial(123.45), "壹佰贰拾叁元肆角伍分");
+        assert_eq!(financial(100.0), "壹佰元整");
+        assert_eq!(financial(100.05), "壹佰元零伍分");
+        assert_eq!(financial(10.5), "壹拾元伍角整");
+        assert_eq!(financial(0.3), "叁角整");
+        assert_eq!(financial(0.0), "零元整");
+
+        assert_eq!(thousands(1234567, ","), "1,234,567");
+        assert_eq!(thousands(-123, ","), "-123");
+        assert_eq!(currency(1234.5, "¥"), "¥1,234.50");
+        assert_eq!(currency(-0.05, "$"), "-$0.05");
+    }
+}
@@ -0,0 +1,156 @@
+use crate::time::DateTime;
+use crate::util;
+use crate::Mode;
+use crate::Others;
+use crate::Rg;
+use std::time::SystemTime;
+
+pub const NANOID_ALPHABET: &str =
+    "_-0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+const CROCKFORD: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const HEX: &[u8] = b"0123456789abcdef";
+
+impl<'a> Rg<'a> {
+    /// 8-4-4-4-12 lowercase hex, random
+    pub fn uuid_v4(&self) -> String {
+        self.others(Others::Uuid4)
+    }
+
+    /// 8-4-4-4-12 lowercase hex, ordered by the current time
+    pub fn uuid_v7(&self) -> String {
+        self.others(Others::Uuid7)
+    }
+
+    /// Time-ordered by `at` instead of the current time
+    pub fn uuid_v7_at(&self, at: DateTime) -> String {
+        self.others(Others::Uuid7At(at))
+    }
+
+    /// 26 chars of Crockford base32, ordered by the current time
+    pub fn ulid(&self) -> String {
+        self.others(Others::Ulid)
+    }
+
+    pub fn ulid_at(&self, at: DateTime) -> String {
+        self.others(Others::UlidAt(at))
+    }
+
+    /// Pass [`NANOID_ALPHABET`] for the standard one
+    pub fn nanoid(&self, len: u32, alphabet: &str) -> String {
+        self.others(Others::NanoId(len..=len, alphabet.to_string()))
+    }
+
+    /// Looks like a digest: 7 for a short git sha, 32 for md5, 40 for sha1, 64 for sha256
+    pub fn hex(&self, len: u32) -> String {
+        self.others(Others::Hex(len..=len))
+    }
+
+    fn others(&self, others: Others) -> String {
+        let mut buf = String::new();
+        let _res = self.core::<&str>(&Mode::Others(others), &mut buf, true, true);
+        buf
+    }
+}
+
+pub(crate) fn push_uuid_v4(buf: &mut String) {
+    let bits: u128 = util::rand_range(0..=u128::MAX);
+    push_uuid(buf, bits & !(0xf << 76) | 0x4 << 76);
+}
+
+pub(crate) fn push_uuid_v7(buf: &mut String, at: Option<DateTime>) {
+    let bits: u128 = util::rand_range(0..=u128::MAX);
+    let ts = (unix_millis(at) as u128 & 0xffff_ffff_ffff) << 80;
+    push_uuid(buf, ts | bits & !(u128::MAX << 76) | 0x7 << 76);
+}
+
+/// Sets the RFC 4122 variant and writes the hyphenated form.
+fn push_uuid(buf: &mut String, bits: u128) {
+    let bits = bits & !(0b11 << 62) | 0b10 << 62;
+
+    for i in (0..32).rev() {
+        if matches!(i, 23 | 19 | 15 | 11) {
+            buf.push('-');
+        }
+        buf.push(HEX[(bits >> (i * 4)) as usize & 0xf] as char);
+    }
+}
+
+pub(crate) fn push_ulid(buf: &mut String, at: Option<DateTime>) {
+    let rand: u128 = util::rand_range(0..=(1 << 80) - 1);
+    let bits = (unix_millis(at) as u128 & 0xffff_ffff_ffff) << 80 | rand;
+
+    for i in (0..26).rev() {
+        buf.push(CROCKFORD[(bits >> (i * 5)) as usize & 0x1f] as char);
+    }
+}
+
+pub(crate) fn push_hex(buf: &mut String, len: u32) {
+    for _ in 0..len {
+        buf.push(*util::rand_slice(HEX) as char);
+    }
+}
+
+pub(crate) fn push_chars(buf: &mut String, len: u32, alphabet: &str) {
+    let chars: Vec<char> = alphabet.chars().collect();
+
+    for _ in 0..len {
+        buf.push(*util::rand_slice(&chars));
+    }
+}
+
+fn unix_millis(at: Option<DateTime>) -> u64 {
+    match at {
+        Some(at) => at.unix_millis() as u64,
+        None => SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::id::NANOID_ALPHABET;
+    use crate::time::DateTime;
+    use crate::Rg;
+
+    #[test]
+    fn uuid() {
+        let rg = Rg::new();
+
+        let v4 = rg.uuid_v4();
+        assert_eq!(v4.len(), 36);
+        assert_eq!(&v4[14..15], "4");
+        assert!(matches!(&v4[19..20], "8" | "9" | "a" | "b"), "{}", v4);
+
+        let early = rg.uuid_v7_at(DateTime::ymd(2020, 1, 1));
+        let late = rg.uuid_v7_at(DateTime::ymd(2021, 1, 1));
+        assert_eq!(&late[14..15], "7");
+        assert!(early < late);
+        println!("{} {}", v4, rg.uuid_v7());
+    }
+
+    #[test]
+    fn ulid() {
+        let rg = Rg::new();
+        let early = rg.ulid_at(DateTime::ymd(2020, 1, 1));
+        let late = rg.ulid_at(DateTime::ymd(2021, 1, 1));
+
+        assert_eq!(early.len(), 26);
+        assert!(early < late);
+        assert_eq!(&rg.ulid_at(DateTime::UNIX_EPOCH)[..10], "0000000000");
+    }
+
+    #[test]
+    fn nanoid_and_hex() {
+        let rg = Rg::with_dec("<", ">");
+
+        let id = rg.nanoid(21, NANOID_ALPHABET);
+        assert_eq!(id.len(), 23);
+        assert!(id[1..22].chars().all(|c| NANOID_ALPHABET.contains(c)));
+
+        let sha = rg.hex(40);
+        assert!(sha[1..41].chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(rg.nanoid(4, "x"), "<xxxx>");
+    }
+}
@@ -1,20 +1,40 @@
 extern crate alloc;
 extern crate rand;
+// `#[derive(Rubbish)]` names the crate `::rg`, also from inside it
+extern crate self as rg;
 
-use enum_len::EnumLen;
-use rand::thread_rng;
-use rand::Rng;
+use combinator::unique::Seen;
+use enum_len::Variants;
+use error::check_range;
+use error::Error;
 use std::borrow::Borrow;
 use std::borrow::Cow;
 use std::ops::RangeInclusive;
+use std::sync::Arc;
 
 pub mod combinator;
+pub mod config;
 mod data;
+pub mod dist;
+pub mod error;
 pub mod extend;
 pub mod fmt;
+pub mod id;
 mod macros;
+pub mod rubbish;
+pub mod space;
+pub mod time;
 mod util;
 
+pub use enum_len::Rubbish;
+pub use rubbish::Rubbish;
+
+/// Seeds the rng of the current thread, everything generated on it afterwards
+/// is reproducible.
+pub fn seed(seed: u64) {
+    util::seed(seed)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Others {
     Lowers(RangeInclusive<u32>),
@@ -22,9 +42,72 @@ pub enum Others {
     LowersAndUppers(RangeInclusive<u32>),
     Digits(RangeInclusive<u32>),
     DigitsNonZero(RangeInclusive<u32>),
+    DateTime(RangeInclusive<time::DateTime>, time::TimeFormat),
+    Duration(RangeInclusive<std::time::Duration>, time::DurationFormat),
+    Uuid4,
+    Uuid7,
+    Uuid7At(time::DateTime),
+    Ulid,
+    UlidAt(time::DateTime),
+    /// custom alphabet, see [`id::NANOID_ALPHABET`]
+    NanoId(RangeInclusive<u32>, String),
+    Hex(RangeInclusive<u32>),
+    Int(RangeInclusive<i64>),
+    Uint(RangeInclusive<u64>),
+    Int128(RangeInclusive<i128>),
+    /// value range scaled by `10^precision`, see [`Others::decimal`]
+    Decimal(RangeInclusive<i64>, u32),
+}
+
+impl Others {
+    /// `Err` for an empty range or alphabet, which would panic when generating
+    pub fn validate(&self) -> error::Result<()> {
+        match self {
+            Others::Lowers(rg)
+            | Others::Uppers(rg)
+            | Others::LowersAndUppers(rg)
+            | Others::Digits(rg)
+            | Others::DigitsNonZero(rg)
+            | Others::Hex(rg) => check_range("length", rg),
+            Others::NanoId(rg, alphabet) => {
+                check_range("length", rg)?;
+                if alphabet.is_empty() && *rg.end() > 0 {
+                    return Err(Error::Empty("nanoid alphabet"));
+                }
+                Ok(())
+            }
+            Others::Int(rg) => check_range("int", rg),
+            Others::Uint(rg) => check_range("uint", rg),
+            Others::Int128(rg) => check_range("int128", rg),
+            Others::Decimal(rg, precision) => {
+                check_decimal_precision(*precision)?;
+                check_range("decimal", rg)
+            }
+            Others::DateTime(rg, _) => check_range("datetime", rg),
+            Others::Duration(rg, _) => check_range("duration", rg),
+            Others::Uuid4
+            | Others::Uuid7
+            | Others::Uuid7At(_)
+            | Others::Ulid
+            | Others::UlidAt(_) => Ok(()),
+        }
+    }
+
+    /// Whether the output is a bare number, e.g. when written as a json value
+    pub(crate) fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Others::Int(_)
+                | Others::Uint(_)
+                | Others::Int128(_)
+                | Others::Decimal(..)
+                | Others::DateTime(_, time::TimeFormat::Unix | time::TimeFormat::UnixMillis)
+                | Others::Duration(_, time::DurationFormat::Secs | time::DurationFormat::Millis)
+        )
+    }
 }
 
-#[derive(EnumLen, Debug, Clone, PartialEq, Eq)]
+#[derive(Variants, Debug, Clone, PartialEq, Eq)]
 pub enum Mode<'a, S> {
     // ---------Borrowed-----------
     Noun,
@@ -38,21 +121,141 @@ pub enum Mode<'a, S> {
     SLP,
     // A = Adverb 状语
     Diy(&'a [S]),
+    /// `Diy` without the borrow, for modes built at runtime or kept around
+    DiyOwned(Arc<[S]>),
     Others(Others),
     ASVO(S),
     SVOA(S),
     ASLP(S),
     SLPA(S),
-    /// **must be last, for working properly**
+    /// every mode one after another
+    Concat(Vec<Mode<'a, S>>),
+    /// one of the modes, equally likely
+    OneOf(Vec<Mode<'a, S>>),
+    /// the mode with a chance in percent, nothing otherwise
+    Optional(Box<Mode<'a, S>>, u8),
+    /// the mode a count of times in the range, separated by `S`
+    Repeat(Box<Mode<'a, S>>, RangeInclusive<u32>, S),
+    /// one of the unit modes above, see [`Rg::rand_pool`]
+    #[variants(skip)]
     Rand,
 }
 
+/// Number of `Mode` variants
+#[deprecated(note = "use `Mode::LEN`")]
+pub const ENUM_LEN: usize = Mode::<&str>::LEN;
+
+/// Most decimals a scaled `i64` can carry
+pub(crate) const MAX_DECIMALS: u32 = 19;
+
+pub(crate) fn check_decimal_precision(precision: u32) -> error::Result<()> {
+    if precision > MAX_DECIMALS {
+        return Err(Error::Invalid(format!(
+            "precision {} is over {} decimals",
+            precision, MAX_DECIMALS
+        )));
+    }
+    Ok(())
+}
+
+/// A mode borrowing nothing, cheap to clone and `Send`
+pub type OwnedMode = Mode<'static, String>;
+
+impl<S> Mode<'_, S> {
+    /// `Mode::DiyOwned` of `items`
+    pub fn diy_owned<I: IntoIterator<Item = S>>(items: I) -> Self {
+        Mode::DiyOwned(items.into_iter().collect())
+    }
+}
+
+impl<S: AsRef<str>> Mode<'_, S> {
+    /// `Err` for an empty `Diy` and the cases of [`Others::validate`]
+    pub fn validate(&self) -> error::Result<()> {
+        match self {
+            Mode::Diy([]) => Err(Error::Empty("Mode::Diy")),
+            Mode::DiyOwned(items) if items.is_empty() => Err(Error::Empty("Mode::Diy")),
+            Mode::Others(others) => others.validate(),
+            Mode::Concat(modes) => modes.iter().try_for_each(Mode::validate),
+            Mode::OneOf(modes) => {
+                if modes.is_empty() {
+                    return Err(Error::Empty("Mode::OneOf"));
+                }
+                modes.iter().try_for_each(Mode::validate)
+            }
+            Mode::Optional(mode, percent) => {
+                if *percent > 100 {
+                    return Err(Error::Invalid(format!("chance {}% is over 100%", percent)));
+                }
+                mode.validate()
+            }
+            Mode::Repeat(mode, times, _) => {
+                check_range("repeat", times)?;
+                mode.validate()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The same mode with its items copied, see [`OwnedMode`]
+    pub fn to_owned_mode(&self) -> OwnedMode {
+        let sep = |s: &S| s.as_ref().to_string();
+        match self {
+            Mode::Noun => Mode::Noun,
+            Mode::Verb => Mode::Verb,
+            Mode::Pred => Mode::Pred,
+            Mode::Adj => Mode::Adj,
+            Mode::Adverb => Mode::Adverb,
+            Mode::SVO => Mode::SVO,
+            Mode::SLP => Mode::SLP,
+            Mode::Diy(items) => Mode::diy_owned(items.iter().map(sep)),
+            Mode::DiyOwned(items) => Mode::diy_owned(items.iter().map(sep)),
+            Mode::Others(others) => Mode::Others(others.clone()),
+            Mode::ASVO(s) => Mode::ASVO(sep(s)),
+            Mode::SVOA(s) => Mode::SVOA(sep(s)),
+            Mode::ASLP(s) => Mode::ASLP(sep(s)),
+            Mode::SLPA(s) => Mode::SLPA(sep(s)),
+            Mode::Concat(modes) => Mode::Concat(modes.iter().map(Mode::to_owned_mode).collect()),
+            Mode::OneOf(modes) => Mode::OneOf(modes.iter().map(Mode::to_owned_mode).collect()),
+            Mode::Optional(mode, percent) => {
+                Mode::Optional(Box::new(mode.to_owned_mode()), *percent)
+            }
+            Mode::Repeat(mode, times, s) => {
+                Mode::Repeat(Box::new(mode.to_owned_mode()), times.clone(), sep(s))
+            }
+            Mode::Rand => Mode::Rand,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Rg<'a> {
     left_dec: Option<&'a str>,
     right_dec: Option<&'a str>,
+    rand_pool: Option<Arc<RandPool>>,
 }
 
+/// Weighted modes `Mode::Rand` picks from, see [`Rg::rand_pool`]
+#[derive(Debug)]
+pub(crate) struct RandPool {
+    pub(crate) modes: Vec<OwnedMode>,
+    pub(crate) weights: Vec<u32>,
+    alias: util::Alias,
+}
+
+impl RandPool {
+    fn pick(&self) -> &OwnedMode {
+        &self.modes[self.alias.sample()]
+    }
+}
+
+impl PartialEq for RandPool {
+    fn eq(&self, other: &Self) -> bool {
+        self.modes == other.modes && self.weights == other.weights
+    }
+}
+
+impl Eq for RandPool {}
+
 pub struct Iter<'a, S> {
     rg: &'a Rg<'a>,
     mode: &'a Mode<'a, S>,
@@ -66,10 +269,50 @@ impl<'a, S: AsRef<str>> Iterator for Iter<'a, S> {
     }
 }
 
+/// Like [`Iter`] but owning its `Rg` and mode, see [`Rg::iter_owned`]
+#[derive(Debug, Clone)]
+pub struct IntoIter<'a, S> {
+    rg: Rg<'a>,
+    mode: Mode<'a, S>,
+}
+
+impl<'a, S: AsRef<str>> Iterator for IntoIter<'a, S> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.rg.once(&self.mode).into_owned())
+    }
+}
+
 impl<'a, S: AsRef<str>> Iter<'a, S> {
     fn new(rg: &'a Rg<'a>, mode: &'a Mode<'a, S>) -> Self {
         Self { rg, mode }
     }
+
+    /// Distinct values, ends after `max_retries` repeats in a row
+    pub fn unique(self, max_retries: u32) -> UniqueIter<'a, S> {
+        UniqueIter {
+            iter: self,
+            seen: Seen::exact(),
+            max_retries,
+        }
+    }
+}
+
+pub struct UniqueIter<'a, S> {
+    iter: Iter<'a, S>,
+    seen: Seen,
+    max_retries: u32,
+}
+
+impl<'a, S: AsRef<str>> Iterator for UniqueIter<'a, S> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        (0..=self.max_retries)
+            .filter_map(|_| self.iter.next())
+            .find(|s| self.seen.insert(s))
+    }
 }
 
 impl<'a> Rg<'a> {
@@ -77,6 +320,7 @@ impl<'a> Rg<'a> {
         Self {
             left_dec: None,
             right_dec: None,
+            rand_pool: None,
         }
     }
 
@@ -84,6 +328,7 @@ impl<'a> Rg<'a> {
         Self {
             left_dec: Some(l),
             right_dec: Some(r),
+            rand_pool: None,
         }
     }
 
@@ -91,6 +336,12 @@ impl<'a> Rg<'a> {
         Iter::new(self, mode)
     }
 
+    /// [`Rg::iter`] that can be stored or, for an `Rg<'static>` and an
+    /// [`OwnedMode`], sent to another thread
+    pub fn iter_owned<S: AsRef<str>>(self, mode: Mode<'a, S>) -> IntoIter<'a, S> {
+        IntoIter { rg: self, mode }
+    }
+
     pub fn reset(&mut self) {
         *self = Self::new();
     }
@@ -105,14 +356,56 @@ impl<'a> Rg<'a> {
         self
     }
 
-    pub(crate) fn push_left(&mut self, buf: &mut String, push_dec: bool) {
+    /// What `Mode::Rand` picks from instead of the single words, `SVO` and
+    /// `SLP`, each mode as often as its weight.
+    ///
+    /// A `Rand` in the pool stands for those builtin modes again. Entries
+    /// weighing 0 are left out, and with none left `Rand` is back to the default.
+    pub fn rand_pool<S: AsRef<str>>(&mut self, pool: &[(Mode<'_, S>, u32)]) -> &mut Self {
+        let (modes, weights): (Vec<_>, Vec<_>) = pool
+            .iter()
+            .filter(|(_, w)| *w > 0)
+            .map(|(mode, w)| (mode.to_owned_mode(), *w))
+            .unzip();
+
+        self.rand_pool = (!modes.is_empty()).then(|| {
+            Arc::new(RandPool {
+                alias: util::Alias::new(&weights),
+                modes,
+                weights,
+            })
+        });
+        self
+    }
+
+    /// [`Rg::rand_pool`] after validating every entry, leaves the pool as it
+    /// was on `Err`
+    pub fn try_rand_pool<S: AsRef<str>>(
+        &mut self,
+        pool: &[(Mode<'_, S>, u32)],
+    ) -> error::Result<&mut Self> {
+        for (mode, _) in pool {
+            mode.validate()?;
+        }
+        Ok(self.rand_pool(pool))
+    }
+
+    /// `Err` for a mode of the `Rand` pool that [`Mode::validate`] refuses
+    fn validate_pool(&self) -> error::Result<()> {
+        match &self.rand_pool {
+            Some(pool) => pool.modes.iter().try_for_each(Mode::validate),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn push_left(&self, buf: &mut String, push_dec: bool) {
         if push_dec {
             if let Some(l) = self.left_dec {
                 buf.push_str(l);
             }
         }
     }
-    pub(crate) fn push_right(&mut self, buf: &mut String, push_dec: bool) {
+    pub(crate) fn push_right(&self, buf: &mut String, push_dec: bool) {
         if push_dec {
             if let Some(r) = self.right_dec {
                 buf.push_str(r);
@@ -138,9 +431,8 @@ impl<'a> Rg<'a> {
         for (i, mode) in modes.iter().enumerate() {
             let _res = self.core(mode.borrow(), &mut buf, true, false);
 
-            if !seps.is_empty() {
-                let idx = if i < seps.len() { i } else { seps.len() - 1 };
-                buf.push_str(unsafe { seps.get_unchecked(idx).as_ref() });
+            if let Some(sep) = seps.get(i).or(seps.last()) {
+                buf.push_str(sep.as_ref());
             }
         }
         self.push_right(&mut buf, true);
@@ -148,6 +440,38 @@ impl<'a> Rg<'a> {
         buf
     }
 
+    /// [`Rg::combine`] after validating every mode
+    pub fn try_combine<'b, S: AsRef<str> + 'b, M: Borrow<Mode<'b, S>>>(
+        &mut self,
+        modes: &[M],
+        seps: &[S],
+    ) -> error::Result<String> {
+        self.try_combine_with_buf(String::new(), modes, seps)
+    }
+
+    pub fn try_combine_with_buf<'b, S: AsRef<str> + 'b, M: Borrow<Mode<'b, S>>>(
+        &mut self,
+        buf: String,
+        modes: &[M],
+        seps: &[S],
+    ) -> error::Result<String> {
+        self.validate_pool()?;
+        for mode in modes {
+            mode.borrow().validate()?;
+        }
+        Ok(self.combine_with_buf(buf, modes, seps))
+    }
+
+    /// [`Rg::once`] after validating `mode` and the `Rand` pool
+    pub fn try_once<'b, S: AsRef<str> + 'b, M: Borrow<Mode<'b, S>> + 'b>(
+        &self,
+        mode: M,
+    ) -> error::Result<Cow<'b, str>> {
+        self.validate_pool()?;
+        mode.borrow().validate()?;
+        Ok(self.once(mode))
+    }
+
     pub fn once<'b, S: AsRef<str> + 'b, M: Borrow<Mode<'b, S>> + 'b>(
         &self,
         mode: M,
@@ -155,11 +479,13 @@ impl<'a> Rg<'a> {
         let mut buf = String::new();
         let ret = self.core(mode.borrow(), &mut buf, false, true);
 
-        if buf.is_empty() {
-            Cow::Borrowed(ret.unwrap())
-        } else {
-            debug_assert!(ret.is_none(), "{:?}", ret);
-            Cow::Owned(buf)
+        match ret {
+            Some(ret) => {
+                debug_assert!(buf.is_empty(), "{:?}", buf);
+                Cow::Borrowed(ret)
+            }
+            // also when nothing was written, e.g. an `Optional` that missed
+            None => Cow::Owned(buf),
         }
     }
 
@@ -185,6 +511,11 @@ impl<'a> Rg<'a> {
                 self.push_others(buf, name);
                 None
             }
+            // owned by the mode, so it can't be handed out as `&'b str`
+            Mode::DiyOwned(s) => {
+                buf.push_str(self.get_diy(s).as_ref());
+                None
+            }
             Mode::SVO => {
                 self.push_svo(buf);
                 None
@@ -209,7 +540,42 @@ impl<'a> Rg<'a> {
                 self.push_slpa(buf, s.as_ref());
                 None
             }
-            Mode::Rand => self.rand_mode(buf, mode),
+            Mode::Concat(modes) => {
+                for mode in modes {
+                    self.core(mode, buf, true, false);
+                }
+                None
+            }
+            Mode::OneOf(modes) => {
+                self.core(util::rand_slice(modes), buf, true, false);
+                None
+            }
+            Mode::Optional(mode, percent) => {
+                if util::rand_range(0..100) < *percent {
+                    self.core(mode, buf, true, false);
+                }
+                None
+            }
+            Mode::Repeat(mode, times, sep) => {
+                for i in 0..util::rand_range(times.clone()) {
+                    if i != 0 {
+                        buf.push_str(sep.as_ref());
+                    }
+                    self.core(mode, buf, true, false);
+                }
+                None
+            }
+            Mode::Rand => match &self.rand_pool {
+                Some(pool) => match pool.pick() {
+                    Mode::Rand => self.rand_mode(buf, mode, push_buf),
+                    // without the pool, so a nested `Rand` can't pick it again
+                    picked => {
+                        Rg::new().core(picked, buf, true, false);
+                        None
+                    }
+                },
+                None => self.rand_mode(buf, mode, push_buf),
+            },
             _ => {
                 let res = match mode {
                     Mode::Noun => self.get_noun(),
@@ -242,21 +608,14 @@ impl<'a> Rg<'a> {
         ret
     }
 
-    fn rand_mode<'b, S2: AsRef<str>>(&self, buf: &mut String, _: &Mode<'b, S2>) -> Option<&'b str> {
-        let idx = thread_rng().gen_range(0..ENUM_LEN as u8 - 7);
-
-        let rmode: &Mode<'_, &str> = match idx {
-            0 => &Mode::Noun,
-            1 => &Mode::Verb,
-            2 => &Mode::Pred,
-            3 => &Mode::Adj,
-            4 => &Mode::Adverb,
-            5 => &Mode::SVO,
-            6 => &Mode::SLP,
-            _ => unreachable!(),
-        };
-
-        self.core(rmode, buf, false, false)
+    fn rand_mode<'b, S2: AsRef<str>>(
+        &self,
+        buf: &mut String,
+        _: &Mode<'b, S2>,
+        push_buf: bool,
+    ) -> Option<&'b str> {
+        let rmode: Mode<'static, &str> = Mode::random_by(|n| util::rand_range(0..n));
+        self.core(&rmode, buf, push_buf, false)
     }
 
     fn get_diy<'b, S2: AsRef<str>>(&self, s: &'b [S2]) -> &'b S2 {
@@ -264,35 +623,35 @@ impl<'a> Rg<'a> {
     }
 
     fn get_pred(&self) -> &'static str {
-        *util::rand_slice(data::PREDS)
+        util::rand_slice::<&str>(data::PREDS)
     }
 
     fn get_adverb(&self) -> &'static str {
-        *util::rand_slice(data::ADVERBS)
+        util::rand_slice::<&str>(data::ADVERBS)
     }
 
     fn get_adj(&self) -> &'static str {
-        *util::rand_slice(data::adjs())
+        util::rand_slice::<&str>(data::adjs())
     }
 
     fn get_noun(&self) -> &'static str {
-        *util::rand_slice(data::nouns())
+        util::rand_slice::<&str>(data::nouns())
     }
 
     fn get_verb(&self) -> &'static str {
-        *util::rand_slice(data::VERBS)
+        util::rand_slice::<&str>(data::VERBS)
     }
 
     fn push_svo(&self, buf: &mut String) {
-        buf.push_str(*util::rand_slice(data::nouns()));
-        buf.push_str(*util::rand_slice(data::VERBS));
-        buf.push_str(*util::rand_slice(data::nouns()));
+        buf.push_str(util::rand_slice::<&str>(data::nouns()));
+        buf.push_str(util::rand_slice::<&str>(data::VERBS));
+        buf.push_str(util::rand_slice::<&str>(data::nouns()));
     }
 
     fn push_slp(&self, buf: &mut String) {
-        buf.push_str(*util::rand_slice(data::nouns()));
-        buf.push_str(*util::rand_slice(data::LINKS));
-        buf.push_str(*util::rand_slice(data::PREDS));
+        buf.push_str(util::rand_slice::<&str>(data::nouns()));
+        buf.push_str(util::rand_slice::<&str>(data::LINKS));
+        buf.push_str(util::rand_slice::<&str>(data::PREDS));
     }
 
     fn push_asvo(&self, buf: &mut String, sep: &str) {
@@ -341,6 +700,186 @@ impl<'a> Rg<'a> {
             Others::LowersAndUppers(rg) => {
                 loop_n!(b"qwertyuiopasdfghjklzxcvbnmQWERTYUIOPASDFGHJKLZXCVBNM", rg)
             }
+            Others::DateTime(rg, fmt) => self.datetime_value(rg.clone()).write(buf, fmt),
+            Others::Duration(rg, fmt) => {
+                time::write_duration(buf, time::rand_duration(rg.clone()), *fmt)
+            }
+            Others::Uuid4 => id::push_uuid_v4(buf),
+            Others::Uuid7 => id::push_uuid_v7(buf, None),
+            Others::Uuid7At(at) => id::push_uuid_v7(buf, Some(*at)),
+            Others::Ulid => id::push_ulid(buf, None),
+            Others::UlidAt(at) => id::push_ulid(buf, Some(*at)),
+            Others::NanoId(rg, alphabet) => {
+                id::push_chars(buf, util::rand_range(rg.clone()), alphabet)
+            }
+            Others::Hex(rg) => id::push_hex(buf, util::rand_range(rg.clone())),
+            Others::Int(rg) => buf.push_str(&util::rand_range(rg.clone()).to_string()),
+            Others::Uint(rg) => buf.push_str(&util::rand_range(rg.clone()).to_string()),
+            Others::Int128(rg) => buf.push_str(&util::rand_range(rg.clone()).to_string()),
+            Others::Decimal(rg, precision) => {
+                extend::push_scaled(buf, util::rand_range(rg.clone()), *precision)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::util;
+    use crate::Mode;
+    use crate::Others;
+    use crate::Rg;
+
+    #[test]
+    fn variants() {
+        #[derive(enum_len::Variants, Debug, PartialEq)]
+        enum Suit {
+            Hearts,
+            Spades,
+            #[variants(skip)]
+            Joker,
+            Other(char),
+        }
+
+        assert_eq!(Suit::LEN, 4);
+        assert_eq!(Suit::NAMES, ["Hearts", "Spades", "Joker", "Other"]);
+        assert_eq!(Suit::UNITS_LEN, 2);
+        assert_eq!(Suit::UNITS, [Suit::Hearts, Suit::Spades]);
+        assert_eq!(Suit::Other('x').variant_index(), 3);
+        assert_eq!(Suit::Joker.variant_name(), "Joker");
+
+        assert_eq!(Suit::random_by(|n| n - 1), Suit::Spades);
+        for _ in 0..10 {
+            let suit = Suit::random_by(|n| util::rand_range(0..n));
+            assert!(suit == Suit::Hearts || suit == Suit::Spades);
+        }
+
+        // no unit variant, so no `random_by`
+        #[derive(enum_len::Variants)]
+        enum Wrapped {
+            #[allow(dead_code)]
+            Id(u32),
+        }
+        assert_eq!((Wrapped::LEN, Wrapped::UNITS_LEN), (1, 0));
+
+        assert_eq!(Mode::<&str>::UNITS.len(), 7);
+        assert!(!Mode::<&str>::UNITS.contains(&Mode::Rand));
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn invalid() {
+        let mut rg = Rg::new();
+        let empty: &[&str] = &[];
+
+        let err = rg.try_once(Mode::Diy(empty)).unwrap_err();
+        assert_eq!(err, Error::Empty("Mode::Diy"));
+        let err = rg
+            .try_combine(&[Mode::<&str>::Others(Others::Digits(3..=1))], &[""])
+            .unwrap_err();
+        println!("{}", err);
+        assert!(matches!(err, Error::EmptyRange(_)));
+
+        // no multiple of 0.1 in there
+        assert!(rg.try_decimal(0.11..=0.19, 1).is_err());
+        // bounds a float product misses by a rounding error
+        assert_eq!(rg.try_decimal(0.07..=0.07, 2), Ok(0.07));
+        assert_eq!(Others::decimal(0.07..=0.5, 2), Others::Decimal(7..=50, 2));
+        assert_eq!(
+            Others::decimal(-0.29..=0.29, 2),
+            Others::Decimal(-29..=29, 2)
+        );
+        assert!(rg.try_decimal(0.1..=0.2, 30).is_err());
+        assert_eq!(rg.try_number(5..=5), Ok(5));
+    }
+
+    #[test]
+    fn owned_mode() {
+        let items: Vec<String> = (1..=3).map(|i| format!("item{}", i)).collect();
+        let mode = Mode::diy_owned(items);
+        let modes = [mode.clone(), Mode::<&str>::Noun.to_owned_mode()];
+
+        let worker = std::thread::spawn(move || {
+            let res: Vec<_> = Rg::new().iter_owned(mode).take(5).collect();
+            res
+        });
+        for res in worker.join().unwrap() {
+            assert!(res.starts_with("item"), "{}", res);
+        }
+
+        let res = Rg::with_dec("<", ">").combine(&modes, &[" ".to_string()]);
+        println!("{}", res);
+        assert!(res.starts_with("<item"));
+        assert!(Rg::new().try_once(Mode::<String>::diy_owned([])).is_err());
+    }
+
+    #[test]
+    fn composite() {
+        let rg = Rg::new();
+        let tag = Mode::Concat(vec![
+            Mode::Diy(&["<"][..]),
+            Mode::Repeat(Box::new(Mode::Diy(&["ab"])), 2..=2, "-"),
+            Mode::Optional(Box::new(Mode::Diy(&["!"])), 0),
+            Mode::OneOf(vec![Mode::Diy(&[">"]), Mode::Diy(&["/>"])]),
+        ]);
+        for _ in 0..10 {
+            let res = rg.once(&tag);
+            assert!(res == "<ab-ab>" || res == "<ab-ab/>", "{}", res);
+        }
+
+        // modes that can write nothing
+        let nothing = [
+            Mode::<&str>::Optional(Box::new(Mode::Noun), 0),
+            Mode::Concat(Vec::new()),
+            Mode::Repeat(Box::new(Mode::Noun), 0..=0, "-"),
+        ];
+        for mode in &nothing {
+            assert_eq!(rg.once(mode), "");
+        }
+        assert_eq!(Rg::new().combine(&nothing, &[","]), ",,,");
+
+        // words picked by `Rand` are kept when nested
+        let mode = Mode::<&str>::Optional(Box::new(Mode::Rand), 100);
+        assert!(!rg.once(&mode).is_empty());
+        assert!(rg.try_once(Mode::<&str>::OneOf(Vec::new())).is_err());
+    }
+
+    #[test]
+    fn rand_pool() {
+        let mut rg = Rg::new();
+        rg.rand_pool(&[
+            (Mode::Diy(&["x"]), 3),
+            (Mode::Others(Others::Digits(2..=2)), 1),
+            (Mode::Noun, 0),
+        ]);
+
+        let mut xs = 0;
+        for _ in 0..1000 {
+            let res = rg.once(Mode::<&str>::Rand);
+            assert!(res == "x" || res.len() == 2, "{}", res);
+            xs += (res == "x") as u32;
         }
+        assert!((650..850).contains(&xs), "{}", xs);
+
+        let nested = Mode::<&str>::Repeat(Box::new(Mode::Rand), 3..=3, "");
+        assert!(rg.once(&nested).len() >= 3);
+
+        let est = rg.estimate::<&str>(&Mode::Rand).unwrap();
+        assert_eq!(est.cardinality, Some(101));
+
+        // entries that write nothing, or can't write at all
+        rg.rand_pool(&[(Mode::<&str>::Optional(Box::new(Mode::Noun), 0), 1)]);
+        assert_eq!(rg.once(Mode::<&str>::Rand), "");
+        let empty: &[&str] = &[];
+        assert!(rg.try_rand_pool(&[(Mode::Diy(empty), 1)]).is_err());
+        rg.rand_pool(&[(Mode::Diy(empty), 1)]);
+        assert_eq!(
+            rg.try_once(Mode::<&str>::Rand),
+            Err(Error::Empty("Mode::Diy"))
+        );
+
+        rg.rand_pool::<&str>(&[]);
+        assert_eq!(rg, Rg::new());
     }
 }
@@ -1,5 +1,6 @@
 #![feature(trace_macros)]
 trace_macros!(true);
+use rg::combinator::dsl;
 use rg::combinator::select;
 use rg::combinator::Generator;
 use rg::combinator::RgBindMode;
@@ -96,5 +97,14 @@ fn main() {
     let res = rg.boolean();
     println!("{:?}", res);
 
+    // 12. 表达式, 例如 cargo run -- '(noun & verb | digits{1,3}) ~ "**" * 3'
+    let src = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "noun & verb".to_string());
+    match dsl::parse(&src) {
+        Ok(g) => println!("{:?}", g.generate()),
+        Err(e) => println!("{}", e),
+    }
+
     println!("ok. cost: {:?}", now.elapsed());
 }
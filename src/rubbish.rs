@@ -0,0 +1,269 @@
+//! Random instances of whole types, e.g. test fixtures.
+//!
+//! ```ignore
+//! use rg::Rubbish;
+//!
+//! #[derive(Rubbish)]
+//! struct User {
+//!     #[rg(mode = "noun")]
+//!     name: String,
+//!     #[rg(range = "18..=65")]
+//!     age: u8,
+//!     tags: Vec<String>,
+//! }
+//!
+//! let user = User::rubbish(&mut rg::Rg::new());
+//! ```
+
+use crate::Mode;
+use crate::Rg;
+use std::cell::Cell;
+use std::ops::RangeInclusive;
+
+/// Nested `Option`s and `Vec`s below this are `None` and empty, and derived
+/// enums only pick variants that don't hold themselves, so that recursive
+/// types end
+const MAX_DEPTH: u32 = 4;
+
+thread_local! {
+    static DEPTH: Cell<u32> = const { Cell::new(0) };
+}
+
+/// A value of `Self` with every part random, see `#[derive(Rubbish)]`
+pub trait Rubbish: Sized {
+    fn rubbish(rg: &mut Rg<'_>) -> Self;
+}
+
+/// Whether values made now are below [`MAX_DEPTH`]. What a derived enum
+/// checks before picking a variant.
+#[doc(hidden)]
+pub fn at_max_depth() -> bool {
+    DEPTH.with(Cell::get) >= MAX_DEPTH
+}
+
+/// `f` one level deeper, `None` below [`MAX_DEPTH`]
+fn nested<T>(f: impl FnOnce() -> T) -> Option<T> {
+    if at_max_depth() {
+        return None;
+    }
+
+    let depth = DEPTH.with(Cell::get);
+    DEPTH.with(|d| d.set(depth + 1));
+    let res = f();
+    DEPTH.with(|d| d.set(depth));
+    Some(res)
+}
+
+macro_rules! rubbish_number {
+    ($($t:ty),*) => {$(
+        impl Rubbish for $t {
+            fn rubbish(rg: &mut Rg<'_>) -> Self {
+                rg.number(<$t>::MIN..=<$t>::MAX)
+            }
+        }
+    )*};
+}
+
+rubbish_number!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl Rubbish for f64 {
+    fn rubbish(rg: &mut Rg<'_>) -> Self {
+        rg.decimal(-1000.0..=1000.0, 2)
+    }
+}
+
+impl Rubbish for f32 {
+    fn rubbish(rg: &mut Rg<'_>) -> Self {
+        f64::rubbish(rg) as f32
+    }
+}
+
+impl Rubbish for bool {
+    fn rubbish(rg: &mut Rg<'_>) -> Self {
+        rg.number(0..=1) == 1
+    }
+}
+
+impl Rubbish for char {
+    fn rubbish(rg: &mut Rg<'_>) -> Self {
+        rg.number(b'a'..=b'z') as char
+    }
+}
+
+/// A noun
+impl Rubbish for String {
+    fn rubbish(rg: &mut Rg<'_>) -> Self {
+        rg.once(Mode::<&str>::Noun).into_owned()
+    }
+}
+
+impl<T: Rubbish> Rubbish for Option<T> {
+    fn rubbish(rg: &mut Rg<'_>) -> Self {
+        if bool::rubbish(rg) {
+            nested(|| T::rubbish(rg))
+        } else {
+            None
+        }
+    }
+}
+
+/// Up to 4 items
+impl<T: Rubbish> Rubbish for Vec<T> {
+    fn rubbish(rg: &mut Rg<'_>) -> Self {
+        rubbish_items(rg, 0..=4)
+    }
+}
+
+/// A count in `len` of items, empty below [`MAX_DEPTH`]. What
+/// `#[rg(len = "..")]` expands to.
+#[doc(hidden)]
+pub fn rubbish_items<T, C>(rg: &mut Rg<'_>, len: RangeInclusive<usize>) -> C
+where
+    T: Rubbish,
+    C: FromIterator<T> + Default,
+{
+    let len = rg.number(len);
+    nested(|| (0..len).map(|_| T::rubbish(rg)).collect()).unwrap_or_default()
+}
+
+/// One level deeper, or at the same level below [`MAX_DEPTH`] where an enum
+/// behind it stops recursing
+impl<T: Rubbish> Rubbish for Box<T> {
+    fn rubbish(rg: &mut Rg<'_>) -> Self {
+        let value = nested(|| T::rubbish(&mut *rg)).unwrap_or_else(|| T::rubbish(rg));
+        Box::new(value)
+    }
+}
+
+macro_rules! rubbish_tuple {
+    ($($t:ident),*) => {
+        impl<$($t: Rubbish),*> Rubbish for ($($t,)*) {
+            fn rubbish(rg: &mut Rg<'_>) -> Self {
+                ($($t::rubbish(rg),)*)
+            }
+        }
+    };
+}
+
+rubbish_tuple!(A, B);
+rubbish_tuple!(A, B, C);
+rubbish_tuple!(A, B, C, D);
+
+#[cfg(test)]
+mod tests {
+    use crate::Rg;
+    use crate::Rubbish;
+
+    #[derive(Rubbish, Debug)]
+    struct User {
+        #[rg(mode = "noun")]
+        name: String,
+        #[rg(digits = "4..=4")]
+        pin: String,
+        #[rg(range = "18..=65")]
+        age: u8,
+        #[rg(len = "1..=3")]
+        roles: Vec<Role>,
+        address: Option<Address>,
+        #[rg(json)]
+        profile: String,
+        #[rg(skip)]
+        visits: u32,
+    }
+
+    #[derive(Rubbish, Debug)]
+    struct Address(#[rg(mode = "noun")] String, #[rg(range = "1..=999")] u16);
+
+    #[derive(Rubbish, Debug, PartialEq)]
+    enum Role {
+        Admin,
+        Guest {
+            days: u8,
+        },
+        #[rg(skip)]
+        #[allow(dead_code)]
+        Root,
+    }
+
+    #[derive(Rubbish, Debug)]
+    struct Menu {
+        label: char,
+        children: Vec<Menu>,
+    }
+
+    #[derive(Rubbish, Debug)]
+    enum Tree {
+        Leaf,
+        Node(Box<Tree>, Box<Tree>),
+    }
+
+    #[derive(Rubbish, Debug)]
+    struct Node {
+        #[rg(len = "1..=2")]
+        kids: Vec<Node>,
+    }
+
+    #[derive(Rubbish, Debug)]
+    struct Pair<T> {
+        left: T,
+        right: Option<T>,
+    }
+
+    fn depth(menu: &Menu) -> usize {
+        assert!(menu.label.is_ascii_lowercase());
+        1 + menu.children.iter().map(depth).max().unwrap_or(0)
+    }
+
+    fn height(tree: &Tree) -> usize {
+        match tree {
+            Tree::Leaf => 1,
+            Tree::Node(left, right) => 1 + height(left).max(height(right)),
+        }
+    }
+
+    #[test]
+    fn fixtures() {
+        let mut rg = Rg::new();
+        for _ in 0..20 {
+            let user = User::rubbish(&mut rg);
+
+            assert!(!user.name.is_empty());
+            assert!(user.pin.len() == 4 && user.pin.bytes().all(|b| b.is_ascii_digit()));
+            assert!((18..=65).contains(&user.age));
+            assert!((1..=3).contains(&user.roles.len()));
+            assert!(!user.roles.contains(&Role::Root));
+            if let Some(Address(street, no)) = &user.address {
+                assert!(!street.is_empty() && (1..=999).contains(no));
+            }
+            assert!(user.profile.starts_with('{'));
+            assert_eq!(user.visits, 0);
+        }
+        println!("{:?}", User::rubbish(&mut rg));
+    }
+
+    #[test]
+    fn recursive() {
+        let mut rg = Rg::new();
+        for _ in 0..20 {
+            let menu = Menu::rubbish(&mut rg);
+            assert!(depth(&menu) <= 5, "{:?}", menu);
+        }
+
+        let heights: Vec<_> = (0..200).map(|_| height(&Tree::rubbish(&mut rg))).collect();
+        assert!(heights.iter().all(|h| *h <= 5), "{:?}", heights);
+        assert!(heights.contains(&5), "{:?}", heights);
+
+        // at least one kid per level until the depth limit
+        let mut node = Node::rubbish(&mut rg);
+        let mut levels = 1;
+        while let Some(kid) = node.kids.pop() {
+            node = kid;
+            levels += 1;
+        }
+        assert_eq!(levels, 5);
+
+        let pair = Pair::<String>::rubbish(&mut rg);
+        println!("{:?}", pair);
+        assert!(!pair.left.is_empty() && pair.right.is_none_or(|r| !r.is_empty()));
+    }
+}
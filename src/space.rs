@@ -0,0 +1,659 @@
+//! Every output a mode or generator can produce, without sampling.
+
+use crate::data;
+use crate::extend;
+use crate::Mode;
+use crate::Others;
+use crate::RandPool;
+use crate::Rg;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+const LOWERS: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const HEX: &str = "0123456789abcdef";
+
+/// A finite description of outputs, see [`Rg::space`] and
+/// [`Generator::space`](crate::combinator::Generator::space).
+///
+/// Branches are not deduplicated, overlapping ones enumerate the same string twice.
+pub enum Space<'a> {
+    /// one of the items
+    Choice(Vec<Cow<'a, str>>),
+    /// integers of the range, written scaled down by `10^precision`
+    Numbers(RangeInclusive<i128>, u32),
+    /// every part one after another
+    Product(Vec<Space<'a>>),
+    /// one of the branches, with the probability to take it
+    Union(Vec<(f64, Space<'a>)>),
+    /// inner outputs passed through a function
+    Map(
+        Box<Space<'a>>,
+        RefCell<Box<dyn FnMut(String) -> String + 'a>>,
+    ),
+    /// a count of inner outputs in the range, each two separated by `sep`
+    Repeat(Box<Space<'a>>, RangeInclusive<u32>, Option<Box<Space<'a>>>),
+    /// random outputs too many to list, with their entropy in bits
+    Opaque(f64),
+}
+
+impl fmt::Debug for Space<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Space::Choice(items) => f.debug_tuple("Choice").field(&items.len()).finish(),
+            Space::Numbers(rg, precision) => {
+                f.debug_tuple("Numbers").field(rg).field(precision).finish()
+            }
+            Space::Product(parts) => f.debug_tuple("Product").field(parts).finish(),
+            Space::Union(branches) => f.debug_tuple("Union").field(branches).finish(),
+            Space::Map(inner, _) => f.debug_tuple("Map").field(inner).finish(),
+            Space::Repeat(inner, times, sep) => f
+                .debug_tuple("Repeat")
+                .field(inner)
+                .field(times)
+                .field(sep)
+                .finish(),
+            Space::Opaque(bits) => f.debug_tuple("Opaque").field(bits).finish(),
+        }
+    }
+}
+
+impl<'a> Space<'a> {
+    pub fn literal<T: Into<Cow<'a, str>>>(s: T) -> Self {
+        Space::Choice(vec![s.into()])
+    }
+
+    pub fn chars(alphabet: &'a str) -> Self {
+        Space::Choice(
+            alphabet
+                .char_indices()
+                .map(|(i, c)| Cow::Borrowed(&alphabet[i..i + c.len_utf8()]))
+                .collect(),
+        )
+    }
+
+    /// Equally likely branches
+    pub fn one_of(branches: Vec<Space<'a>>) -> Self {
+        let p = 1.0 / branches.len() as f64;
+        Space::Union(branches.into_iter().map(|s| (p, s)).collect())
+    }
+
+    /// The same space borrowing nothing, `None` for a `Map`
+    fn to_owned_space<'b>(&self) -> Option<Space<'b>> {
+        let all = |spaces: &[Space<'_>]| {
+            spaces
+                .iter()
+                .map(Space::to_owned_space)
+                .collect::<Option<Vec<_>>>()
+        };
+
+        Some(match self {
+            Space::Choice(items) => {
+                Space::Choice(items.iter().map(|s| Cow::Owned(s.to_string())).collect())
+            }
+            Space::Numbers(rg, precision) => Space::Numbers(rg.clone(), *precision),
+            Space::Product(parts) => Space::Product(all(parts)?),
+            Space::Union(branches) => {
+                let mut owned = Vec::new();
+                for (p, s) in branches {
+                    owned.push((*p, s.to_owned_space()?));
+                }
+                Space::Union(owned)
+            }
+            Space::Map(..) => return None,
+            Space::Repeat(inner, times, sep) => Space::Repeat(
+                Box::new(inner.to_owned_space()?),
+                times.clone(),
+                match sep {
+                    Some(sep) => Some(Box::new(sep.to_owned_space()?)),
+                    None => None,
+                },
+            ),
+            Space::Opaque(bits) => Space::Opaque(*bits),
+        })
+    }
+
+    /// Number of outputs, `None` if it does not fit a `u128`
+    pub fn len(&self) -> Option<u128> {
+        match self {
+            Space::Choice(items) => Some(items.len() as u128),
+            Space::Numbers(rg, _) => Some(if rg.is_empty() {
+                0
+            } else {
+                rg.end().abs_diff(*rg.start()) + 1
+            }),
+            Space::Product(parts) => parts
+                .iter()
+                .try_fold(1u128, |acc, part| acc.checked_mul(part.len()?)),
+            Space::Union(branches) => branches
+                .iter()
+                .try_fold(0u128, |acc, (_, s)| acc.checked_add(s.len()?)),
+            Space::Map(inner, _) => inner.len(),
+            Space::Repeat(inner, times, sep) => times
+                .clone()
+                .try_fold(0u128, |acc, k| acc.checked_add(repeat_len(inner, sep, k)?)),
+            Space::Opaque(_) => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// The `idx`-th output, the last part of a product changing fastest
+    pub fn get(&self, idx: u128) -> Option<String> {
+        let mut buf = String::new();
+        self.write(idx, &mut buf).then_some(buf)
+    }
+
+    fn write(&self, mut idx: u128, buf: &mut String) -> bool {
+        match self {
+            Space::Choice(items) => match items.get(idx as usize) {
+                Some(item) if idx < items.len() as u128 => {
+                    buf.push_str(item);
+                    true
+                }
+                _ => false,
+            },
+            Space::Numbers(rg, precision) => {
+                if self.len().is_none_or(|len| idx >= len) {
+                    return false;
+                }
+                let n = rg.start() + idx as i128;
+                if *precision == 0 {
+                    buf.push_str(&n.to_string());
+                } else {
+                    extend::push_scaled(buf, n as i64, *precision);
+                }
+                true
+            }
+            Space::Product(parts) => write_product(parts.iter().collect(), idx, buf),
+            Space::Union(branches) => {
+                for (_, branch) in branches {
+                    match branch.len() {
+                        Some(len) if idx < len => return branch.write(idx, buf),
+                        Some(len) => idx -= len,
+                        None => return false,
+                    }
+                }
+                false
+            }
+            Space::Map(inner, f) => {
+                let mut own = String::new();
+                if !inner.write(idx, &mut own) {
+                    return false;
+                }
+                buf.push_str(&(f.borrow_mut())(own));
+                true
+            }
+            Space::Repeat(inner, times, sep) => {
+                for k in times.clone() {
+                    match repeat_len(inner, sep, k) {
+                        Some(len) if idx < len => {
+                            let mut parts = Vec::new();
+                            for i in 0..k {
+                                if let (true, Some(sep)) = (i != 0, sep) {
+                                    parts.push(&**sep);
+                                }
+                                parts.push(&**inner);
+                            }
+                            return write_product(parts, idx, buf);
+                        }
+                        Some(len) => idx -= len,
+                        None => return false,
+                    }
+                }
+                false
+            }
+            Space::Opaque(_) => false,
+        }
+    }
+
+    /// Size and entropy, assuming every choice is uniform unless weighted
+    pub fn estimate(&self) -> Estimate {
+        let (bits, collision) = self.stats();
+        Estimate {
+            cardinality: self.len(),
+            bits,
+            collision,
+        }
+    }
+
+    /// Shannon entropy in bits and the chance two outputs are the same
+    fn stats(&self) -> (f64, f64) {
+        fn uniform(n: f64) -> (f64, f64) {
+            if n <= 1.0 {
+                (0.0, 1.0)
+            } else {
+                (n.log2(), 1.0 / n)
+            }
+        }
+
+        match self {
+            Space::Choice(items) => uniform(items.len() as f64),
+            Space::Numbers(rg, _) => uniform(if rg.is_empty() {
+                0.0
+            } else {
+                (*rg.end() as f64 - *rg.start() as f64) + 1.0
+            }),
+            Space::Product(parts) => parts.iter().fold((0.0, 1.0), |(bits, c), part| {
+                let (b, pc) = part.stats();
+                (bits + b, c * pc)
+            }),
+            Space::Union(branches) => mix(branches.iter().map(|(p, s)| (*p, s.stats()))),
+            Space::Map(inner, _) => inner.stats(),
+            Space::Repeat(inner, times, sep) => {
+                let (ib, ic) = inner.stats();
+                let (sb, sc) = sep.as_ref().map_or((0.0, 1.0), |s| s.stats());
+                mix(times.clone().map(|k| {
+                    let gaps = k.saturating_sub(1) as f64;
+                    let k = k as f64;
+                    (1.0, (k * ib + gaps * sb, ic.powf(k) * sc.powf(gaps)))
+                }))
+            }
+            Space::Opaque(bits) => (*bits, (-bits).exp2()),
+        }
+    }
+
+    /// All outputs in order, `None` if there are more than a `u128` can count
+    pub fn outputs(self) -> Option<Outputs<'a>> {
+        let len = self.len()?;
+        Some(Outputs {
+            space: self,
+            next: 0,
+            len,
+        })
+    }
+}
+
+/// Branches taken with the weights, as if no two of them overlap
+fn mix(branches: impl Iterator<Item = (f64, (f64, f64))>) -> (f64, f64) {
+    let branches: Vec<_> = branches.filter(|(w, _)| *w > 0.0).collect();
+    let total: f64 = branches.iter().map(|(w, _)| w).sum();
+
+    branches.iter().fold((0.0, 0.0), |(bits, c), (w, (b, bc))| {
+        let p = w / total;
+        (bits + p * (b - p.log2()), c + p * p * bc)
+    })
+}
+
+/// How many distinct outputs a configuration has and how evenly they come up,
+/// see [`Space::estimate`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    /// distinct outputs, `None` if it can't be counted or overflows a `u128`
+    ///
+    /// An upper bound: equal items of a `Choice` or overlapping branches of a
+    /// `Union` are counted twice, as are strings a `Product` or `Repeat` can
+    /// build from more than one sequence of parts, e.g. `"ab"` from `"a"+"b"`
+    /// and `"ab"+""`. In those cases `bits` overestimates and the collision
+    /// chance underestimates too.
+    pub cardinality: Option<u128>,
+    /// Shannon entropy of one output in bits
+    pub bits: f64,
+    /// chance of two outputs being the same
+    collision: f64,
+}
+
+impl Estimate {
+    /// Chance of at least one repeat among `n` outputs (birthday bound)
+    pub fn collision_probability(&self, n: u64) -> f64 {
+        let pairs = n as f64 * n.saturating_sub(1) as f64 / 2.0;
+        -(-pairs * self.collision).exp_m1()
+    }
+
+    /// About how many outputs can be drawn before a repeat has chance `p`
+    pub fn outputs_until_collision(&self, p: f64) -> f64 {
+        assert!(0.0 < p && p < 1.0, "probability out of 0..1: {}", p);
+        (2.0 * -(-p).ln_1p() / self.collision).sqrt()
+    }
+}
+
+fn repeat_len(inner: &Space, sep: &Option<Box<Space>>, k: u32) -> Option<u128> {
+    if k == 0 {
+        return Some(1);
+    }
+    let sep_len = sep.as_ref().map_or(Some(1), |s| s.len())?;
+    inner
+        .len()?
+        .checked_pow(k)?
+        .checked_mul(sep_len.checked_pow(k - 1)?)
+}
+
+fn write_product(parts: Vec<&Space>, mut idx: u128, buf: &mut String) -> bool {
+    let mut digits = vec![0; parts.len()];
+
+    for (i, part) in parts.iter().enumerate().rev() {
+        match part.len() {
+            Some(0) | None => return false,
+            Some(len) => {
+                digits[i] = idx % len;
+                idx /= len;
+            }
+        }
+    }
+
+    idx == 0 && parts.iter().zip(digits).all(|(part, d)| part.write(d, buf))
+}
+
+/// Iterator over [`Space::outputs`]
+#[derive(Debug)]
+pub struct Outputs<'a> {
+    space: Space<'a>,
+    next: u128,
+    len: u128,
+}
+
+impl Outputs<'_> {
+    pub fn len(&self) -> u128 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Iterator for Outputs<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.next >= self.len {
+            return None;
+        }
+        self.next += 1;
+        self.space.get(self.next - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let rest = usize::try_from(self.len - self.next).unwrap_or(usize::MAX);
+        (rest, usize::try_from(self.len - self.next).ok())
+    }
+}
+
+impl<'a> Rg<'a> {
+    /// What `once(mode)` can produce, `None` for time and other modes without
+    /// a practical description
+    pub fn space<'b, S: AsRef<str>>(&self, mode: &'b Mode<'b, S>) -> Option<Space<'b>>
+    where
+        'a: 'b,
+    {
+        let pool = self.rand_pool.as_deref();
+        Some(self.decorate(vec![mode_space(mode, pool)?]))
+    }
+
+    /// What `combine(modes, seps)` can produce
+    pub fn combine_space<'b, S: AsRef<str>>(
+        &self,
+        modes: &'b [Mode<'b, S>],
+        seps: &'b [S],
+    ) -> Option<Space<'b>>
+    where
+        'a: 'b,
+    {
+        let mut parts = Vec::new();
+        for (i, mode) in modes.iter().enumerate() {
+            parts.push(mode_space(mode, self.rand_pool.as_deref())?);
+            if let Some(sep) = seps.get(i).or(seps.last()) {
+                parts.push(Space::literal(sep.as_ref()));
+            }
+        }
+        Some(self.decorate(parts))
+    }
+
+    /// Size and entropy of `once(mode)`, see [`Estimate`]
+    pub fn estimate<S: AsRef<str>>(&self, mode: &Mode<'_, S>) -> Option<Estimate> {
+        Some(self.space(mode)?.estimate())
+    }
+
+    /// Size and entropy of `combine(modes, seps)`
+    pub fn combine_estimate<S: AsRef<str>>(
+        &self,
+        modes: &[Mode<'_, S>],
+        seps: &[S],
+    ) -> Option<Estimate> {
+        Some(self.combine_space(modes, seps)?.estimate())
+    }
+
+    /// Every output of `once(mode)` in a fixed order
+    pub fn enumerate<'b, S: AsRef<str>>(&self, mode: &'b Mode<'b, S>) -> Option<Outputs<'b>>
+    where
+        'a: 'b,
+    {
+        self.space(mode)?.outputs()
+    }
+
+    fn decorate<'b>(&self, mut parts: Vec<Space<'b>>) -> Space<'b>
+    where
+        'a: 'b,
+    {
+        if let Some(l) = self.left_dec {
+            parts.insert(0, Space::literal(l));
+        }
+        if let Some(r) = self.right_dec {
+            parts.push(Space::literal(r));
+        }
+        Space::Product(parts)
+    }
+}
+
+fn words<'a>(words: &'static [&'static str]) -> Space<'a> {
+    Space::Choice(words.iter().map(|w| Cow::Borrowed(*w)).collect())
+}
+
+fn svo<'a>() -> Space<'a> {
+    Space::Product(vec![
+        words(data::nouns()),
+        words(data::VERBS),
+        words(data::nouns()),
+    ])
+}
+
+fn slp<'a>() -> Space<'a> {
+    Space::Product(vec![
+        words(data::nouns()),
+        words(data::LINKS),
+        words(data::PREDS),
+    ])
+}
+
+/// `pool` is what `Rand` picks from, see [`Rg::rand_pool`]
+pub(crate) fn mode_space<'b, S: AsRef<str>>(
+    mode: &'b Mode<'b, S>,
+    pool: Option<&RandPool>,
+) -> Option<Space<'b>> {
+    let all = |modes: &'b [Mode<'b, S>]| {
+        modes
+            .iter()
+            .map(|mode| mode_space(mode, pool))
+            .collect::<Option<Vec<_>>>()
+    };
+
+    let adverb_around = |before: bool, sep: &'b S, sentence: Space<'b>| {
+        let mut parts = vec![words(data::ADVERBS), Space::literal(sep.as_ref()), sentence];
+        if !before {
+            parts.reverse();
+        }
+        Space::Product(parts)
+    };
+
+    Some(match mode {
+        Mode::Noun => words(data::nouns()),
+        Mode::Verb => words(data::VERBS),
+        Mode::Pred => words(data::PREDS),
+        Mode::Adj => words(data::adjs()),
+        Mode::Adverb => words(data::ADVERBS),
+        Mode::SVO => svo(),
+        Mode::SLP => slp(),
+        Mode::Diy(s) => Space::Choice(s.iter().map(|s| Cow::Borrowed(s.as_ref())).collect()),
+        Mode::DiyOwned(s) => Space::Choice(s.iter().map(|s| Cow::Borrowed(s.as_ref())).collect()),
+        Mode::Others(others) => others_space(others)?,
+        Mode::ASVO(sep) => adverb_around(true, sep, svo()),
+        Mode::SVOA(sep) => adverb_around(false, sep, svo()),
+        Mode::ASLP(sep) => adverb_around(true, sep, slp()),
+        Mode::SLPA(sep) => adverb_around(false, sep, slp()),
+        Mode::Concat(modes) => Space::Product(all(modes)?),
+        Mode::OneOf(modes) => Space::one_of(all(modes)?),
+        Mode::Optional(mode, percent) => {
+            let p = f64::from((*percent).min(100)) / 100.0;
+            let mut branches = vec![(p, mode_space(mode, pool)?), (1.0 - p, Space::literal(""))];
+            branches.retain(|(p, _)| *p > 0.0);
+            Space::Union(branches)
+        }
+        Mode::Repeat(mode, times, sep) => Space::Repeat(
+            Box::new(mode_space(mode, pool)?),
+            times.clone(),
+            Some(Box::new(Space::literal(sep.as_ref()))),
+        ),
+        Mode::Rand => match pool {
+            Some(pool) => {
+                let total: f64 = pool.weights.iter().map(|w| f64::from(*w)).sum();
+                let mut branches = Vec::new();
+                for (mode, w) in pool.modes.iter().zip(&pool.weights) {
+                    // copied, the pool may not live as long as `mode`
+                    let space = mode_space(mode, None)?;
+                    branches.push((f64::from(*w) / total, space.to_owned_space()?));
+                }
+                Space::Union(branches)
+            }
+            None => rand_space(),
+        },
+    })
+}
+
+/// The builtin modes `Rand` picks from
+fn rand_space<'a>() -> Space<'a> {
+    Space::one_of(vec![
+        words(data::nouns()),
+        words(data::VERBS),
+        words(data::PREDS),
+        words(data::adjs()),
+        words(data::ADVERBS),
+        svo(),
+        slp(),
+    ])
+}
+
+fn others_space(others: &Others) -> Option<Space<'_>> {
+    fn repeat<'a>(alphabet: Space<'a>, rg: &RangeInclusive<u32>) -> Space<'a> {
+        Space::Repeat(Box::new(alphabet), rg.clone(), None)
+    }
+
+    Some(match others {
+        Others::Lowers(rg) => repeat(Space::chars(LOWERS), rg),
+        Others::Uppers(rg) => repeat(Space::chars(UPPERS), rg),
+        Others::LowersAndUppers(rg) => repeat(
+            Space::one_of(vec![Space::chars(LOWERS), Space::chars(UPPERS)]),
+            rg,
+        ),
+        Others::Digits(rg) => repeat(Space::chars(DIGITS), rg),
+        Others::DigitsNonZero(rg) => repeat(Space::chars(&DIGITS[1..]), rg),
+        Others::Hex(rg) => repeat(Space::chars(HEX), rg),
+        Others::NanoId(rg, alphabet) => repeat(Space::chars(alphabet), rg),
+        Others::Int(rg) => Space::Numbers(*rg.start() as i128..=*rg.end() as i128, 0),
+        Others::Uint(rg) => Space::Numbers(*rg.start() as i128..=*rg.end() as i128, 0),
+        Others::Int128(rg) => Space::Numbers(rg.clone(), 0),
+        Others::Decimal(rg, precision) => {
+            Space::Numbers(*rg.start() as i128..=*rg.end() as i128, *precision)
+        }
+        // the timestamp bits are not random
+        Others::Uuid4 => Space::Opaque(122.0),
+        Others::Uuid7 | Others::Uuid7At(_) => Space::Opaque(74.0),
+        Others::Ulid | Others::UlidAt(_) => Space::Opaque(80.0),
+        Others::DateTime(..) | Others::Duration(..) => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Mode;
+    use crate::Others;
+    use crate::Rg;
+
+    #[test]
+    fn modes() {
+        let rg = Rg::with_dec("<", ">");
+        let mode: Mode<&str> = Mode::Diy(&["a", "b"]);
+        let res: Vec<_> = rg.enumerate(&mode).unwrap().collect();
+        assert_eq!(res, ["<a>", "<b>"]);
+
+        let mode: Mode<&str> = Mode::Others(Others::Digits(0..=2));
+        let res: Vec<_> = Rg::new().enumerate(&mode).unwrap().collect();
+        assert_eq!(res.len(), 111);
+        assert_eq!((&res[0][..], &res[1][..], &res[110][..]), ("", "0", "99"));
+
+        let mode: Mode<&str> = Mode::Others(Others::decimal(-0.1..=0.1, 2));
+        let res: Vec<_> = Rg::new().enumerate(&mode).unwrap().collect();
+        assert_eq!(res.first().unwrap(), "-0.10");
+        assert_eq!(res.len(), 21);
+
+        let mode: Mode<&str> = Mode::Others(Others::Uuid4);
+        assert!(Rg::new().enumerate(&mode).is_none());
+    }
+
+    #[test]
+    fn combine() {
+        let modes = [Mode::Diy(&["x", "y"][..]), Mode::Others(Others::Int(1..=2))];
+        let space = Rg::new().combine_space(&modes, &[",", ";"]).unwrap();
+        let res: Vec<_> = space.outputs().unwrap().collect();
+
+        assert_eq!(res, ["x,1;", "x,2;", "y,1;", "y,2;"]);
+        for s in &res {
+            assert!(s.len() == Rg::new().combine(&modes, &[",", ";"]).len());
+        }
+    }
+
+    #[test]
+    fn estimate() {
+        let rg = Rg::new();
+        let est = rg
+            .estimate::<&str>(&Mode::Others(Others::Digits(4..=4)))
+            .unwrap();
+        assert_eq!(est.cardinality, Some(10_000));
+        assert!((est.bits - 10_000f64.log2()).abs() < 1e-9);
+        assert!(est.collision_probability(1) == 0.0);
+        assert!(est.collision_probability(1000) > 0.99);
+        assert!((est.outputs_until_collision(0.5) - 117.7).abs() < 1.0);
+
+        let est = rg.estimate::<&str>(&Mode::Others(Others::Uuid4)).unwrap();
+        assert_eq!(est.cardinality, None);
+        assert!(est.collision_probability(1_000_000_000) < 1e-18);
+
+        let modes = [Mode::Diy(&["a", "b"][..]), Mode::Diy(&["a", "b"][..])];
+        let est = rg.combine_estimate(&modes, &[" "]).unwrap();
+        println!("{:?}", est);
+        assert_eq!(est.cardinality, Some(4));
+        assert!((est.bits - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sentences() {
+        let space = Rg::new().space::<&str>(&Mode::SVO).unwrap();
+        let len = space.len().unwrap();
+        let last = space.get(len - 1).unwrap();
+
+        assert!(space.get(len).is_none());
+        assert!(!last.is_empty());
+    }
+
+    #[test]
+    fn composite() {
+        let mode = Mode::Concat(vec![
+            Mode::Diy(&["a", "b"][..]),
+            Mode::Optional(Box::new(Mode::Diy(&["!"])), 50),
+            Mode::Repeat(Box::new(Mode::Diy(&["x"])), 1..=2, ","),
+        ]);
+        let res: Vec<_> = Rg::new().enumerate(&mode).unwrap().collect();
+        assert_eq!(
+            res,
+            ["a!x", "a!x,x", "ax", "ax,x", "b!x", "b!x,x", "bx", "bx,x"]
+        );
+
+        let est = Rg::new().estimate(&mode).unwrap();
+        assert_eq!(est.cardinality, Some(8));
+        assert!((est.bits - 3.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,521 @@
+use crate::util;
+use crate::Rg;
+use std::cmp::Ordering;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::ops::Add;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// A point in time with millisecond resolution, displayed at a fixed UTC offset.
+///
+/// Compares by instant only, the same moment at two offsets is equal.
+#[derive(Debug, Clone, Copy)]
+pub struct DateTime {
+    millis: i64,
+    /// minutes east of UTC, only affects display
+    offset: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// 2026-10-18T08:05:09.123+08:00
+    Rfc3339,
+    /// seconds since epoch
+    Unix,
+    /// milliseconds since epoch
+    UnixMillis,
+    /// 2026年10月18日
+    Chinese,
+    /// 2026年10月18日 08时05分09秒
+    ChineseDateTime,
+    /// strftime-like: %Y %y %m %d %H %M %S %f(millis) %z(+0800) %:z(+08:00) %s %%
+    Pattern(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationFormat {
+    /// P1DT2H3M4.005S
+    Iso8601,
+    /// 1d 2h 3m 4s 5ms
+    Human,
+    Secs,
+    Millis,
+}
+
+impl DateTime {
+    pub const UNIX_EPOCH: Self = Self::from_unix_millis(0);
+
+    pub const fn from_unix(secs: i64) -> Self {
+        Self::from_unix_millis(secs * 1000)
+    }
+
+    pub const fn from_unix_millis(millis: i64) -> Self {
+        Self { millis, offset: 0 }
+    }
+
+    /// Midnight UTC of the given day, panics on an invalid date.
+    pub fn ymd(year: i32, month: u32, day: u32) -> Self {
+        Self::ymd_hms(year, month, day, 0, 0, 0)
+    }
+
+    /// The given UTC wall time, panics on an invalid date or time.
+    pub fn ymd_hms(year: i32, month: u32, day: u32, hour: u32, min: u32, sec: u32) -> Self {
+        assert!(
+            (1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day),
+            "invalid date: {}-{}-{}",
+            year,
+            month,
+            day
+        );
+        assert!(
+            hour < 24 && min < 60 && sec < 60,
+            "invalid time: {}:{}:{}",
+            hour,
+            min,
+            sec
+        );
+
+        let days = days_from_civil(year as i64, month as i64, day as i64);
+        let secs = days * 86_400 + hour as i64 * 3600 + min as i64 * 60 + sec as i64;
+        Self::from_unix(secs)
+    }
+
+    /// Same instant, displayed at `minutes` east of UTC.
+    pub const fn with_offset(mut self, minutes: i32) -> Self {
+        self.offset = minutes;
+        self
+    }
+
+    pub const fn offset(&self) -> i32 {
+        self.offset
+    }
+
+    pub const fn unix(&self) -> i64 {
+        self.millis.div_euclid(1000)
+    }
+
+    pub const fn unix_millis(&self) -> i64 {
+        self.millis
+    }
+
+    /// `self + d`, `None` when it does not fit in the millisecond range
+    pub fn checked_add(self, d: Duration) -> Option<Self> {
+        let millis = i64::try_from(d.as_millis()).ok()?;
+        Some(Self {
+            millis: self.millis.checked_add(millis)?,
+            offset: self.offset,
+        })
+    }
+
+    pub fn format(&self, fmt: &TimeFormat) -> String {
+        let mut buf = String::new();
+        self.write(&mut buf, fmt);
+        buf
+    }
+
+    pub fn write(&self, buf: &mut String, fmt: &TimeFormat) {
+        use std::fmt::Write;
+
+        let p = self.parts();
+
+        let _ = match fmt {
+            TimeFormat::Rfc3339 => {
+                let _ = write!(
+                    buf,
+                    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+                    p.year, p.month, p.day, p.hour, p.min, p.sec
+                );
+                if p.milli != 0 {
+                    let _ = write!(buf, ".{:03}", p.milli);
+                }
+                if self.offset == 0 {
+                    buf.push('Z');
+                } else {
+                    self.write_offset(buf, true);
+                }
+                Ok(())
+            }
+            TimeFormat::Unix => write!(buf, "{}", self.unix()),
+            TimeFormat::UnixMillis => write!(buf, "{}", self.millis),
+            TimeFormat::Chinese => write!(buf, "{}年{}月{}日", p.year, p.month, p.day),
+            TimeFormat::ChineseDateTime => write!(
+                buf,
+                "{}年{}月{}日 {:02}时{:02}分{:02}秒",
+                p.year, p.month, p.day, p.hour, p.min, p.sec
+            ),
+            TimeFormat::Pattern(pat) => {
+                let mut chars = pat.chars();
+
+                while let Some(c) = chars.next() {
+                    if c != '%' {
+                        buf.push(c);
+                        continue;
+                    }
+
+                    let _ = match chars.next() {
+                        Some('Y') => write!(buf, "{:04}", p.year),
+                        Some('y') => write!(buf, "{:02}", p.year.rem_euclid(100)),
+                        Some('m') => write!(buf, "{:02}", p.month),
+                        Some('d') => write!(buf, "{:02}", p.day),
+                        Some('H') => write!(buf, "{:02}", p.hour),
+                        Some('M') => write!(buf, "{:02}", p.min),
+                        Some('S') => write!(buf, "{:02}", p.sec),
+                        Some('f') => write!(buf, "{:03}", p.milli),
+                        Some('s') => write!(buf, "{}", self.unix()),
+                        Some('z') => {
+                            self.write_offset(buf, false);
+                            Ok(())
+                        }
+                        Some(':') if chars.clone().next() == Some('z') => {
+                            chars.next();
+                            self.write_offset(buf, true);
+                            Ok(())
+                        }
+                        Some(other) => {
+                            buf.push('%');
+                            if other != '%' {
+                                buf.push(other);
+                            }
+                            Ok(())
+                        }
+                        None => {
+                            buf.push('%');
+                            Ok(())
+                        }
+                    };
+                }
+                Ok(())
+            }
+        };
+    }
+
+    fn write_offset(&self, buf: &mut String, colon: bool) {
+        use std::fmt::Write;
+
+        let sign = if self.offset < 0 { '-' } else { '+' };
+        let abs = self.offset.unsigned_abs();
+        let colon = if colon { ":" } else { "" };
+        let _ = write!(buf, "{}{:02}{}{:02}", sign, abs / 60, colon, abs % 60);
+    }
+
+    fn parts(&self) -> Parts {
+        let local = self.millis + self.offset as i64 * 60_000;
+        let days = local.div_euclid(MILLIS_PER_DAY);
+        let in_day = local.rem_euclid(MILLIS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+
+        Parts {
+            year,
+            month,
+            day,
+            hour: (in_day / 3_600_000) as u32,
+            min: (in_day / 60_000 % 60) as u32,
+            sec: (in_day / 1000 % 60) as u32,
+            milli: (in_day % 1000) as u32,
+        }
+    }
+}
+
+impl PartialEq for DateTime {
+    fn eq(&self, other: &Self) -> bool {
+        self.millis == other.millis
+    }
+}
+
+impl Eq for DateTime {}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DateTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.millis.cmp(&other.millis)
+    }
+}
+
+impl Hash for DateTime {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.millis.hash(state);
+    }
+}
+
+impl Add<Duration> for DateTime {
+    type Output = Self;
+
+    /// Panics when the result does not fit, see [`DateTime::checked_add`]
+    fn add(self, d: Duration) -> Self {
+        self.checked_add(d)
+            .expect("overflow when adding duration to DateTime")
+    }
+}
+
+struct Parts {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    min: u32,
+    sec: u32,
+    milli: u32,
+}
+
+/// Random instants that only move forward, for log-like sequences.
+///
+/// Ends when the next instant would not fit in a [`DateTime`].
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    current: Option<DateTime>,
+    step: RangeInclusive<Duration>,
+}
+
+impl Iterator for Timeline {
+    type Item = DateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ret = self.current?;
+        self.current = ret.checked_add(rand_duration(self.step.clone()));
+        Some(ret)
+    }
+}
+
+impl<'a> Rg<'a> {
+    pub fn datetime(&self, rg: RangeInclusive<DateTime>, fmt: &TimeFormat) -> String {
+        self.datetime_with_buf(String::new(), rg, fmt)
+    }
+
+    pub fn datetime_with_buf(
+        &self,
+        mut buf: String,
+        rg: RangeInclusive<DateTime>,
+        fmt: &TimeFormat,
+    ) -> String {
+        self.push_left(&mut buf, true);
+        self.datetime_value(rg).write(&mut buf, fmt);
+        self.push_right(&mut buf, true);
+        buf
+    }
+
+    /// The offset of the range start is used for the result.
+    pub fn datetime_value(&self, rg: RangeInclusive<DateTime>) -> DateTime {
+        let (start, end) = rg.into_inner();
+        let millis = util::rand_range(start.millis..=end.millis);
+
+        DateTime {
+            millis,
+            offset: start.offset,
+        }
+    }
+
+    pub fn duration(&self, rg: RangeInclusive<Duration>, fmt: DurationFormat) -> String {
+        self.duration_with_buf(String::new(), rg, fmt)
+    }
+
+    pub fn duration_with_buf(
+        &self,
+        mut buf: String,
+        rg: RangeInclusive<Duration>,
+        fmt: DurationFormat,
+    ) -> String {
+        self.push_left(&mut buf, true);
+        write_duration(&mut buf, rand_duration(rg), fmt);
+        self.push_right(&mut buf, true);
+        buf
+    }
+
+    /// Millisecond resolution.
+    pub fn duration_value(&self, rg: RangeInclusive<Duration>) -> Duration {
+        rand_duration(rg)
+    }
+
+    /// Starts at `start`, every next instant is `step` later.
+    pub fn timeline(&self, start: DateTime, step: RangeInclusive<Duration>) -> Timeline {
+        Timeline {
+            current: Some(start),
+            step,
+        }
+    }
+}
+
+pub(crate) fn rand_duration(rg: RangeInclusive<Duration>) -> Duration {
+    let (start, end) = rg.into_inner();
+    Duration::from_millis(util::rand_range(
+        start.as_millis() as u64..=end.as_millis() as u64,
+    ))
+}
+
+pub(crate) fn write_duration(buf: &mut String, d: Duration, fmt: DurationFormat) {
+    use std::fmt::Write;
+
+    let millis = d.as_millis() as u64;
+    let (days, hours) = (millis / 86_400_000, millis / 3_600_000 % 24);
+    let (mins, secs, ms) = (millis / 60_000 % 60, millis / 1000 % 60, millis % 1000);
+
+    let _ = match fmt {
+        DurationFormat::Secs => write!(buf, "{}", millis / 1000),
+        DurationFormat::Millis => write!(buf, "{}", millis),
+        DurationFormat::Human => {
+            let mut space = "";
+            for (n, unit) in [
+                (days, "d"),
+                (hours, "h"),
+                (mins, "m"),
+                (secs, "s"),
+                (ms, "ms"),
+            ] {
+                if n != 0 {
+                    let _ = write!(buf, "{}{}{}", space, n, unit);
+                    space = " ";
+                }
+            }
+            if millis == 0 {
+                buf.push_str("0s");
+            }
+            Ok(())
+        }
+        DurationFormat::Iso8601 => {
+            buf.push('P');
+            if days != 0 {
+                let _ = write!(buf, "{}D", days);
+            }
+            if hours != 0 || mins != 0 || secs != 0 || ms != 0 || days == 0 {
+                buf.push('T');
+                if hours != 0 {
+                    let _ = write!(buf, "{}H", hours);
+                }
+                if mins != 0 {
+                    let _ = write!(buf, "{}M", mins);
+                }
+                if ms != 0 {
+                    let _ = write!(buf, "{}.{:03}S", secs, ms);
+                } else if secs != 0 || millis == 0 {
+                    let _ = write!(buf, "{}S", secs);
+                }
+            }
+            Ok(())
+        }
+    };
+}
+
+fn is_leap(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+
+    (yoe + era * 400 + (m <= 2) as i64, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DateTime;
+    use super::DurationFormat;
+    use super::TimeFormat;
+    use crate::Rg;
+    use std::time::Duration;
+
+    #[test]
+    fn format() {
+        let t = DateTime::ymd_hms(2026, 10, 18, 0, 5, 9).with_offset(8 * 60);
+
+        assert_eq!(t.format(&TimeFormat::Rfc3339), "2026-10-18T08:05:09+08:00");
+        assert_eq!(t.format(&TimeFormat::Chinese), "2026年10月18日");
+        assert_eq!(t.format(&TimeFormat::Unix), "1792281909");
+        assert_eq!(
+            t.format(&TimeFormat::Pattern("%Y/%m/%d %H:%M:%S.%f %z %%".into())),
+            "2026/10/18 08:05:09.000 +0800 %"
+        );
+        assert_eq!(
+            DateTime::from_unix(-1).format(&TimeFormat::Rfc3339),
+            "1969-12-31T23:59:59Z"
+        );
+    }
+
+    #[test]
+    fn datetime() {
+        let rg = Rg::with_dec("\"", "\"");
+        let start = DateTime::ymd(2000, 2, 28);
+        let end = DateTime::ymd(2000, 3, 1);
+
+        for _ in 0..100 {
+            let t = rg.datetime_value(start..=end);
+            assert!(start <= t && t <= end);
+        }
+        println!("{}", rg.datetime(start..=end, &TimeFormat::ChineseDateTime));
+    }
+
+    #[test]
+    fn duration() {
+        let rg = Rg::new();
+        let d = Duration::from_millis(90_061_005);
+
+        assert_eq!(rg.duration(d..=d, DurationFormat::Human), "1d 1h 1m 1s 5ms");
+        assert_eq!(
+            rg.duration(d..=d, DurationFormat::Iso8601),
+            "P1DT1H1M1.005S"
+        );
+        assert_eq!(
+            rg.duration(Duration::ZERO..=Duration::ZERO, DurationFormat::Iso8601),
+            "PT0S"
+        );
+    }
+
+    #[test]
+    fn timeline() {
+        let rg = Rg::new();
+        let step = Duration::from_secs(1)..=Duration::from_secs(60);
+        let times: Vec<_> = rg.timeline(DateTime::UNIX_EPOCH, step).take(50).collect();
+
+        assert!(times.windows(2).all(|w| w[0] < w[1]));
+
+        let end = DateTime::from_unix_millis(i64::MAX - 1);
+        let step = Duration::from_millis(1)..=Duration::from_millis(1);
+        assert_eq!(rg.timeline(end, step).count(), 2);
+    }
+
+    #[test]
+    fn instant() {
+        let t = DateTime::ymd(2026, 10, 18);
+
+        assert_eq!(t, t.with_offset(8 * 60));
+        assert!(t.with_offset(8 * 60) < t + Duration::from_millis(1));
+        assert_eq!(t.checked_add(Duration::MAX), None);
+        assert_eq!(
+            DateTime::from_unix_millis(i64::MAX).checked_add(Duration::from_millis(1)),
+            None
+        );
+    }
+}
@@ -1,16 +1,31 @@
 use rand::distributions::uniform::SampleRange;
 use rand::distributions::uniform::SampleUniform;
-use rand::thread_rng;
+use rand::rngs::StdRng;
 use rand::Rng;
+use rand::SeedableRng;
+use std::cell::RefCell;
 
-pub fn rand_slice<T>(s: &[T]) -> &T {
-    let idx = thread_rng().gen_range(0..s.len());
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+pub fn seed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
 
-    unsafe { s.get_unchecked(idx) }
+/// Panics on an empty slice
+pub fn rand_slice<T>(s: &[T]) -> &T {
+    assert!(!s.is_empty(), "nothing to pick from an empty slice");
+    &s[rand_range(0..s.len())]
 }
 
 pub fn rand_or() -> bool {
-    thread_rng().gen()
+    RNG.with(|rng| rng.borrow_mut().gen())
+}
+
+/// `true` with probability `p`
+pub fn rand_bool(p: f64) -> bool {
+    RNG.with(|rng| rng.borrow_mut().gen_bool(p))
 }
 
 pub fn rand_range<T, R>(range: R) -> T
@@ -18,5 +33,73 @@ where
     T: SampleUniform,
     R: SampleRange<T>,
 {
-    thread_rng().gen_range(range)
+    RNG.with(|rng| rng.borrow_mut().gen_range(range))
+}
+
+/// Vose's alias method, O(1) weighted picks of an index
+#[derive(Debug, Clone)]
+pub struct Alias {
+    prob: Vec<f64>,
+    alias: Vec<usize>,
+}
+
+impl Alias {
+    pub fn new<W: Into<f64> + Copy>(weights: &[W]) -> Self {
+        let total: f64 = weights.iter().map(|w| (*w).into()).sum();
+        assert!(total > 0.0, "weights must not be empty or all zero");
+
+        let n = weights.len();
+        let mut prob: Vec<f64> = weights
+            .iter()
+            .map(|w| (*w).into() * n as f64 / total)
+            .collect();
+        let mut alias = vec![0; n];
+        let (mut small, mut large): (Vec<_>, Vec<_>) = (0..n).partition(|i| prob[*i] < 1.0);
+
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            alias[s] = l;
+            prob[l] -= 1.0 - prob[s];
+
+            if prob[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // leftovers are 1.0 up to rounding
+        for i in small.into_iter().chain(large) {
+            prob[i] = 1.0;
+        }
+
+        Self { prob, alias }
+    }
+
+    pub fn sample(&self) -> usize {
+        let i = rand_range(0..self.prob.len());
+
+        if rand_range(0.0..1.0) < self.prob[i] {
+            i
+        } else {
+            self.alias[i]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Alias;
+
+    #[test]
+    fn alias() {
+        super::seed(7);
+        let alias = Alias::new(&[70u32, 0, 30]);
+        let mut counts = [0; 3];
+
+        for _ in 0..10_000 {
+            counts[alias.sample()] += 1;
+        }
+
+        assert_eq!(counts[1], 0);
+        assert!((6700..7300).contains(&counts[0]), "{:?}", counts);
+    }
 }